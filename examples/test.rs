@@ -10,6 +10,7 @@ fn main() {
         .allow_digit_separator(tinytoken::Choice::Yes('_'))
         .add_symbols(&['{', '}', '(', ')', ';', '#', ',', '[', ']'])
         .add_operators(&['+', '-', '*', '%', '/', '&'])
+        .line_comment("//")
         .ignore_numbers(true)
         .build(TO_PARSE);
     // A little comment 77777.
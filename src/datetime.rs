@@ -0,0 +1,194 @@
+/// Matches a TOML date and/or time at the start of `text`: `1979-05-27`, `07:32:00`,
+/// `1979-05-27T07:32:00.999`, `1979-05-27 07:32:00Z`, `1979-05-27T00:32:00-07:00`, ...
+///
+/// Returns the length of the literal in bytes and whether it is a possible date and time.
+pub(crate) fn match_date_time(text: &str) -> Option<(usize, bool)> {
+    let text = text.as_bytes();
+    let (mut len, mut valid) = match date(text) {
+        Some(date_valid) => (10, date_valid),
+        None => time(text)?,
+    };
+
+    if len == 10 && matches!(text.get(10), Some(b'T' | b't' | b' ')) {
+        if let Some((time_len, time_valid)) = time(&text[11..]) {
+            len = 11 + time_len;
+            valid &= time_valid;
+            if let Some((offset_len, offset_valid)) = offset(&text[len..]) {
+                len += offset_len;
+                valid &= offset_valid;
+            }
+        }
+    }
+
+    // A literal running into a word or a number is something else
+    if text
+        .get(len)
+        .is_some_and(|c| c.is_ascii_alphanumeric() || *c == b'_')
+    {
+        return None;
+    }
+    Some((len, valid))
+}
+
+/// Parses exactly `len` ASCII digits at the start of `text`
+fn number(text: &[u8], len: usize) -> Option<u32> {
+    let digits = text.get(..len)?;
+    digits.iter().try_fold(0, |acc, c| {
+        c.is_ascii_digit().then(|| acc * 10 + (c - b'0') as u32)
+    })
+}
+
+/// Matches `YYYY-MM-DD`, returning whether the day exists
+fn date(text: &[u8]) -> Option<bool> {
+    let year = number(text, 4)?;
+    let month = number(text.get(5..)?, 2)?;
+    let day = number(text.get(8..)?, 2)?;
+    if text[4] != b'-' || text[7] != b'-' {
+        return None;
+    }
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+    Some((1..=12).contains(&month) && (1..=days).contains(&day))
+}
+
+/// Matches `HH:MM:SS` with optional fractional seconds, returning its length and whether the
+/// time exists (a leap second is allowed)
+fn time(text: &[u8]) -> Option<(usize, bool)> {
+    let hour = number(text, 2)?;
+    let minute = number(text.get(3..)?, 2)?;
+    let second = number(text.get(6..)?, 2)?;
+    if text[2] != b':' || text[5] != b':' {
+        return None;
+    }
+
+    let mut len = 8;
+    if text.get(8) == Some(&b'.') {
+        let fraction = text[9..].iter().take_while(|c| c.is_ascii_digit()).count();
+        if fraction > 0 {
+            len += 1 + fraction;
+        }
+    }
+    Some((len, hour < 24 && minute < 60 && second <= 60))
+}
+
+/// Matches a UTC offset (`Z`, `+02:00`, `-07:00`), returning its length and whether it exists
+fn offset(text: &[u8]) -> Option<(usize, bool)> {
+    match text.first()? {
+        b'Z' | b'z' => Some((1, true)),
+        b'+' | b'-' => {
+            let hour = number(&text[1..], 2)?;
+            let minute = number(text.get(4..)?, 2)?;
+            (text[3] == b':').then_some((6, hour < 24 && minute < 60))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TokenType, Tokenizer};
+
+    #[test]
+    fn toml_shapes() {
+        for literal in [
+            "1979-05-27",
+            "07:32:00",
+            "00:32:00.999999",
+            "1979-05-27T07:32:00",
+            "1979-05-27t07:32:00",
+            "1979-05-27 07:32:00",
+            "1979-05-27T07:32:00Z",
+            "1979-05-27T00:32:00.999999-07:00",
+            "1979-05-27T00:32:00+14:00",
+        ] {
+            assert_eq!(
+                match_date_time(literal),
+                Some((literal.len(), true)),
+                "{literal}"
+            );
+        }
+    }
+
+    #[test]
+    fn stops_at_the_end_of_the_literal() {
+        assert_eq!(match_date_time("1979-05-27 and"), Some((10, true)));
+        assert_eq!(match_date_time("1979-05-27T"), None);
+        assert_eq!(match_date_time("07:32:00."), Some((8, true)));
+        assert_eq!(match_date_time("07:32:00+01:00"), Some((8, true)));
+        assert_eq!(match_date_time("1979-05-27x"), None);
+        assert_eq!(match_date_time("1979-5-27"), None);
+        assert_eq!(match_date_time("07:32"), None);
+        assert_eq!(match_date_time("1979"), None);
+    }
+
+    #[test]
+    fn leap_years() {
+        assert_eq!(match_date_time("2024-02-29"), Some((10, true)));
+        assert_eq!(match_date_time("2000-02-29"), Some((10, true)));
+        assert_eq!(match_date_time("2023-02-29"), Some((10, false)));
+        assert_eq!(match_date_time("1900-02-29"), Some((10, false)));
+        assert_eq!(match_date_time("2024-02-30"), Some((10, false)));
+    }
+
+    #[test]
+    fn impossible_dates_and_times() {
+        assert_eq!(match_date_time("2024-04-31"), Some((10, false)));
+        assert_eq!(match_date_time("2024-13-01"), Some((10, false)));
+        assert_eq!(match_date_time("2024-00-10"), Some((10, false)));
+        assert_eq!(match_date_time("2024-01-00"), Some((10, false)));
+        assert_eq!(match_date_time("24:00:00"), Some((8, false)));
+        assert_eq!(match_date_time("23:60:00"), Some((8, false)));
+        assert_eq!(match_date_time("23:59:60"), Some((8, true)));
+        assert_eq!(match_date_time("23:59:61"), Some((8, false)));
+    }
+
+    #[test]
+    fn offsets() {
+        assert_eq!(match_date_time("1979-05-27T07:32:00z"), Some((20, true)));
+        assert_eq!(
+            match_date_time("1979-05-27T07:32:00-23:59"),
+            Some((25, true))
+        );
+        assert_eq!(
+            match_date_time("1979-05-27T07:32:00+24:00"),
+            Some((25, false))
+        );
+        assert_eq!(
+            match_date_time("1979-05-27T07:32:00+01:60"),
+            Some((25, false))
+        );
+        // Not an offset, left for the next token
+        assert_eq!(
+            match_date_time("1979-05-27T07:32:00+0100"),
+            Some((19, true))
+        );
+    }
+
+    #[test]
+    fn skipped_when_numbers_are_ignored() {
+        let types = |ignore_numbers| {
+            Tokenizer::builder()
+                .date_time_literals(true)
+                .add_duration_units(&["s"])
+                .ignore_numbers(ignore_numbers)
+                .build("2024-05-01 30s 42")
+                .tokenize()
+                .unwrap()
+                .iter()
+                .map(|token| token.r#type)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            types(false)[..2],
+            [TokenType::DateTime, TokenType::Duration]
+        );
+        assert_eq!(types(true), [TokenType::Word; 3]);
+    }
+}
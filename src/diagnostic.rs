@@ -0,0 +1,238 @@
+use std::fmt::{Display, Write};
+
+use crate::error::TokenizationError;
+use crate::{DigitSeparatorRule, Span};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const BLUE: &str = "\x1b[1;34m";
+const CYAN: &str = "\x1b[1;36m";
+
+/// How serious a [Diagnostic] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The input could not be tokenized
+    Error,
+    /// The input was tokenized but is suspicious
+    Warning,
+    /// Additional information
+    Note,
+}
+
+impl Severity {
+    fn color(&self) -> &'static str {
+        match self {
+            Severity::Error => "\x1b[1;31m",
+            Severity::Warning => "\x1b[1;33m",
+            Severity::Note => "\x1b[1;32m",
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// A report about a location of the input, ready to be shown to a user
+///
+/// Format: Formats Diagnostic as `<severity>[<code>]: <message> at <loc>`, use
+/// [Diagnostic::render] to include the source line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How serious the diagnostic is
+    pub severity: Severity,
+    /// Stable identifier of the kind of diagnostic (e.g., `T0001`)
+    pub code: &'static str,
+    /// Main message, describing the problem
+    pub message: String,
+    /// Short text shown under the highlighted characters
+    pub label: String,
+    /// Highlighted part of the input. Only its first line is underlined, and at least one
+    /// character is always highlighted
+    pub span: Span,
+    /// Hint on how to fix the problem
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    /// Renders the diagnostic along with the offending line of `src`, underlining the highlighted
+    /// characters. ANSI colors are used when `color` is set.
+    ///
+    /// `src` must be the input the diagnostic was produced from.
+    pub fn render(&self, src: &str, color: bool) -> String {
+        let paint = |style: &'static str| if color { style } else { "" };
+        let reset = paint(RESET);
+
+        let start = self.span.start;
+        let line = src.lines().nth(start.line).unwrap_or_default();
+        let line_number = (start.line + 1).to_string();
+        let gutter = " ".repeat(line_number.len());
+
+        // Mirroring tabs keeps the carets aligned with the rendered line
+        let padding: String = line
+            .chars()
+            .chain(std::iter::repeat(' '))
+            .take(start.column)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let available = line.chars().count().saturating_sub(start.column);
+        let len = if self.span.end.line == start.line {
+            self.span.end.column - start.column
+        } else {
+            available
+        };
+        let carets = "^".repeat(len.min(available).max(1));
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{}{}[{}]{reset}{}: {}{reset}",
+            paint(self.severity.color()),
+            self.severity,
+            self.code,
+            paint(BOLD),
+            self.message,
+        );
+        let _ = writeln!(out, "{gutter}{}-->{reset} {}", paint(BLUE), start);
+        let _ = writeln!(out, "{gutter} {}|{reset}", paint(BLUE));
+        let _ = writeln!(out, "{}{line_number} |{reset} {line}", paint(BLUE));
+        let _ = writeln!(
+            out,
+            "{gutter} {}|{reset} {padding}{}{carets} {}{reset}",
+            paint(BLUE),
+            paint(self.severity.color()),
+            self.label,
+        );
+        if let Some(help) = &self.help {
+            let _ = writeln!(out, "{gutter} {}|{reset}", paint(BLUE));
+            let _ = writeln!(
+                out,
+                "{gutter} {}={reset} {}help{reset}: {help}",
+                paint(BLUE),
+                paint(CYAN),
+            );
+        }
+        out
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {} at {}",
+            self.severity, self.code, self.message, self.span.start
+        )
+    }
+}
+
+impl TokenizationError {
+    /// Describes the error as a [Diagnostic]
+    pub fn diagnostic(&self) -> Diagnostic {
+        let (code, message, label, help) = match self {
+            TokenizationError::NotAValidChar(_) => (
+                "T0001",
+                "character literal must contain exactly one character".to_string(),
+                "this character literal",
+                Some("use a string for zero or several characters".to_string()),
+            ),
+            TokenizationError::UnexpectedDigitSeparator(_, rule) => (
+                "T0002",
+                "unexpected digit separator".to_string(),
+                match rule {
+                    DigitSeparatorRule::Leading => "not allowed before the first digit",
+                    DigitSeparatorRule::Trailing => "not allowed after the last digit",
+                    DigitSeparatorRule::Consecutive => "several separators in a row",
+                    DigitSeparatorRule::AdjacentToDot => "not allowed next to the decimal point",
+                    DigitSeparatorRule::AfterRadixPrefix => "not allowed after the radix prefix",
+                    DigitSeparatorRule::NotFollowedByDigit => "not followed by a digit",
+                },
+                Some(match rule {
+                    DigitSeparatorRule::Consecutive => "keep a single digit separator".to_string(),
+                    _ => "remove the digit separator".to_string(),
+                }),
+            ),
+            TokenizationError::UnterminatedComment(_) => (
+                "T0003",
+                "unterminated block comment".to_string(),
+                "comment starts here",
+                Some("close the comment before the end of the input".to_string()),
+            ),
+            TokenizationError::UnterminatedString(_) => (
+                "T0004",
+                "unterminated string".to_string(),
+                "string starts here",
+                Some("add the closing delimiter of the string".to_string()),
+            ),
+            TokenizationError::UnterminatedChar(_) => (
+                "T0005",
+                "unterminated character literal".to_string(),
+                "character literal starts here",
+                Some("add a closing `'`".to_string()),
+            ),
+            TokenizationError::MalformedExponent(_) => (
+                "T0006",
+                "exponent has no digit".to_string(),
+                "expected digits after this exponent",
+                None,
+            ),
+            TokenizationError::UnknownNumberSuffix(_) => (
+                "T0007",
+                "unknown number suffix".to_string(),
+                "not an allowed suffix",
+                Some("separate the number from the following word".to_string()),
+            ),
+            TokenizationError::InvalidEscape(_) => (
+                "T0008",
+                "invalid escape sequence".to_string(),
+                "this escape sequence",
+                Some("escape the backslash itself with `\\\\`".to_string()),
+            ),
+            TokenizationError::UnexpectedCharacter(_, c) => (
+                "T0009",
+                format!("unexpected character {c:?}"),
+                "not part of any token",
+                None,
+            ),
+            TokenizationError::EmptyRadixLiteral(_) => (
+                "T0010",
+                "missing digits after radix prefix".to_string(),
+                "expected digits after this prefix",
+                None,
+            ),
+            TokenizationError::InvalidDigitForRadix(_, c) => (
+                "T0011",
+                format!("invalid digit {c:?} in prefixed number"),
+                "not a digit of this radix",
+                Some("separate the number from the following word".to_string()),
+            ),
+            TokenizationError::InvalidDateTime(_) => (
+                "T0012",
+                "invalid date or time".to_string(),
+                "this date or time does not exist",
+                None,
+            ),
+            TokenizationError::RawStringFenceTooLong(_) => (
+                "T0013",
+                "too many hashes around raw string".to_string(),
+                "this fence is too long",
+                Some("use fewer `#` around the string".to_string()),
+            ),
+        };
+
+        Diagnostic {
+            severity: Severity::Error,
+            code,
+            message,
+            label: label.to_string(),
+            span: self.span(),
+            help,
+        }
+    }
+}
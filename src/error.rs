@@ -1,10 +1,10 @@
 use std::fmt::Display;
 
-use crate::Loc;
+use crate::{DigitSeparatorRule, Loc, Span};
 
 /// `TokenizationError` represents errors that can occur during the tokenization process.
 ///
-/// This enum provides detailed error types with associated location data (`Loc`), indicating
+/// This enum provides detailed error types with associated location data ([Span]), indicating
 /// where in the input text the error occurred.
 #[derive(Debug)]
 pub enum TokenizationError {
@@ -14,15 +14,120 @@ pub enum TokenizationError {
     /// form a valid character, this error is raised.
     ///
     /// ### Fields
-    /// - [Loc]: The line and column location in the input where the error occurred.
-    NotAValidChar(Loc),
+    /// - [Span]: The part of the input where the error occurred.
+    NotAValidChar(Span),
     /// Represents an unexpected digit separator error.
     /// This error is triggered when a digit separator appears in an invalid position within a
     /// number, or if a separator is encountered without surrounding digits.
     ///
     /// ### Fields
-    /// - [Loc]: The line and column location in the input where the error occurred.
-    UnexpectedDigitSeparator(Loc),
+    /// - [Span]: The part of the input where the error occurred.
+    /// - [DigitSeparatorRule]: The rule of [crate::TokenizerConfig::digit_separators] that the
+    ///   separator breaks.
+    UnexpectedDigitSeparator(Span, DigitSeparatorRule),
+    /// Represents a block comment that is never closed.
+    /// This error is triggered when the input ends before the closing delimiter of a block
+    /// comment (or of one of its nested comments) is found.
+    ///
+    /// ### Fields
+    /// - [Span]: The comment, from its opening delimiter to the end of the input.
+    UnterminatedComment(Span),
+    /// Represents a string literal that is never closed.
+    /// This error is triggered when the input ends before the closing quote, or when a line
+    /// break is found while strings are not allowed to span multiple lines.
+    ///
+    /// ### Fields
+    /// - [Span]: The literal, from its opening quote to where the scan stopped.
+    UnterminatedString(Span),
+    /// Represents a character literal that is never closed.
+    /// This error is triggered when the end of the line or of the input is reached before the
+    /// closing quote.
+    ///
+    /// ### Fields
+    /// - [Span]: The literal, from its opening quote to where the scan stopped.
+    UnterminatedChar(Span),
+    /// Represents an exponent without digits.
+    /// This error is triggered when the exponent marker of a number is followed by a sign but no
+    /// digit, as in `1e+`.
+    ///
+    /// ### Fields
+    /// - [Span]: The exponent marker and its sign.
+    MalformedExponent(Span),
+    /// Represents a suffix glued to a number that is not part of the configured suffixes.
+    /// This error is only raised when [crate::TokenizerConfig::number_suffixes] is not empty.
+    ///
+    /// ### Fields
+    /// - [Span]: The suffix.
+    UnknownNumberSuffix(Span),
+    /// Represents a malformed escape sequence in a string or character literal.
+    /// This error is triggered by invalid `\x` or `\u` sequences (bad digits, lone surrogates,
+    /// values outside of Unicode), and by unknown escapes when
+    /// [crate::TokenizerConfig::unknown_escapes] is [crate::EscapePolicy::Error].
+    ///
+    /// ### Fields
+    /// - [Span]: The escape sequence, from its backslash.
+    InvalidEscape(Span),
+    /// Represents a character that fits no token category.
+    /// This error is triggered when a character is neither whitespace, a symbol, an operator,
+    /// the start of a literal nor allowed to start a word by
+    /// [crate::TokenizerConfig::identifier_policy].
+    ///
+    /// ### Fields
+    /// - [Span]: The character.
+    /// - [char]: The unexpected character.
+    UnexpectedCharacter(Span, char),
+    /// Represents a radix prefix (`0x`, `0o`, `0b`) without any digit after it.
+    ///
+    /// ### Fields
+    /// - [Span]: The prefix.
+    EmptyRadixLiteral(Span),
+    /// Represents a digit or letter glued to a prefixed number that is not valid in its radix.
+    /// This error is triggered by literals such as `0b102` or `0o8`, and by letters that are not
+    /// one of the configured [crate::TokenizerConfig::number_suffixes].
+    ///
+    /// ### Fields
+    /// - [Span]: The invalid character.
+    /// - [char]: The invalid character.
+    InvalidDigitForRadix(Span, char),
+    /// Represents a date or time literal that does not exist, such as `2023-02-29` or
+    /// `24:00:00`.
+    /// This error is only raised when [crate::TokenizerConfig::date_time_literals] is set.
+    ///
+    /// ### Fields
+    /// - [Span]: The literal.
+    InvalidDateTime(Span),
+    /// Represents a raw string opened with more hashes than allowed by
+    /// [crate::TokenizerConfig::raw_string_max_hashes], as in `r##"..."##` with a maximum of 1.
+    ///
+    /// ### Fields
+    /// - [Span]: The opening fence: prefix, hashes and quote.
+    RawStringFenceTooLong(Span),
+}
+
+impl TokenizationError {
+    /// Returns the location in the input where the error occurred
+    pub fn loc(&self) -> Loc {
+        self.span().start.loc()
+    }
+
+    /// Returns the part of the input where the error occurred
+    pub fn span(&self) -> Span {
+        match self {
+            TokenizationError::NotAValidChar(span)
+            | TokenizationError::UnexpectedDigitSeparator(span, _)
+            | TokenizationError::UnterminatedComment(span)
+            | TokenizationError::UnterminatedString(span)
+            | TokenizationError::UnterminatedChar(span)
+            | TokenizationError::MalformedExponent(span)
+            | TokenizationError::UnknownNumberSuffix(span)
+            | TokenizationError::InvalidEscape(span)
+            | TokenizationError::UnexpectedCharacter(span, _)
+            | TokenizationError::EmptyRadixLiteral(span)
+            | TokenizationError::InvalidDigitForRadix(span, _)
+            | TokenizationError::InvalidDateTime(span)
+            | TokenizationError::RawStringFenceTooLong(span) => *span,
+        }
+    }
 }
 
 impl std::error::Error for TokenizationError {
@@ -38,10 +143,71 @@ impl std::error::Error for TokenizationError {
 impl Display for TokenizationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            TokenizationError::NotAValidChar(loc) => write!(f, "No valid character at {}", loc),
-            TokenizationError::UnexpectedDigitSeparator(loc) => {
-                write!(f, "Unexpected digit separator at {}", loc)
+            TokenizationError::NotAValidChar(span) => {
+                write!(f, "No valid character at {}", span.start)
+            }
+            TokenizationError::UnexpectedDigitSeparator(span, _) => {
+                write!(f, "Unexpected digit separator at {}", span.start)
+            }
+            TokenizationError::UnterminatedComment(span) => {
+                write!(f, "Unterminated comment starting at {}", span.start)
+            }
+            TokenizationError::UnterminatedString(span) => {
+                write!(f, "Unterminated string starting at {}", span.start)
+            }
+            TokenizationError::UnterminatedChar(span) => {
+                write!(f, "Unterminated character starting at {}", span.start)
+            }
+            TokenizationError::MalformedExponent(span) => {
+                write!(f, "Malformed exponent at {}", span.start)
+            }
+            TokenizationError::UnknownNumberSuffix(span) => {
+                write!(f, "Unknown number suffix at {}", span.start)
+            }
+            TokenizationError::InvalidEscape(span) => {
+                write!(f, "Invalid escape sequence at {}", span.start)
+            }
+            TokenizationError::UnexpectedCharacter(span, c) => {
+                write!(f, "Unexpected character {:?} at {}", c, span.start)
+            }
+            TokenizationError::EmptyRadixLiteral(span) => {
+                write!(f, "Missing digits after radix prefix at {}", span.start)
             }
+            TokenizationError::InvalidDigitForRadix(span, c) => {
+                write!(f, "Invalid digit {:?} for radix at {}", c, span.start)
+            }
+            TokenizationError::InvalidDateTime(span) => {
+                write!(f, "Invalid date or time at {}", span.start)
+            }
+            TokenizationError::RawStringFenceTooLong(span) => {
+                write!(f, "Too many hashes around raw string at {}", span.start)
+            }
+        }
+    }
+}
+
+/// `NumberError` represents errors that can occur when reading the value of a number token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberError {
+    /// The token is not a [crate::TokenType::Number].
+    NotANumber,
+    /// An integer was requested from a [crate::NumberType::Float] token.
+    NotAnInteger,
+    /// The value does not fit in the requested type.
+    Overflow,
+    /// The digits of the token do not form a valid number (e.g., no digit at all).
+    Invalid,
+}
+
+impl std::error::Error for NumberError {}
+
+impl Display for NumberError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumberError::NotANumber => write!(f, "Token is not a number"),
+            NumberError::NotAnInteger => write!(f, "Number is not an integer"),
+            NumberError::Overflow => write!(f, "Number is too large for the requested type"),
+            NumberError::Invalid => write!(f, "Invalid number"),
         }
     }
 }
@@ -1,17 +1,33 @@
 #![doc = include_str!("../README.md")]
 
-use error::TokenizationError;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
 
+use error::{NumberError, TokenizationError};
+use num::BigUint;
+use trie::Trie;
+
+mod datetime;
+/// Contains diagnostics rendering for tokenization errors
+pub mod diagnostic;
 /// Contains error definitions specific to tokenization
 pub mod error;
+/// Contains conversions between positions and columns in various encodings
+pub mod line_index;
+/// Contains numeric helpers for the values of number tokens
+pub mod num;
+mod trie;
 
 /// Represents the types of numeric tokens recognized by the tokenizer
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum NumberType {
-    /// Floating-point numbers (e.g., `3.14`, `.25`)
+    /// Floating-point numbers (e.g., `3.14`, `.25`, `1.5e10`)
     Float,
     /// Hexadecimal numbers (e.g., `0x1A3F`)
     Hex,
+    /// Hexadecimal floating-point numbers, with a binary exponent (e.g., `0x1.8p3`)
+    HexFloat,
     /// Binary numbers (e.g., `0b1010`)
     Binary,
     /// Octal numbers (e.g., `0o755`)
@@ -20,13 +36,33 @@ pub enum NumberType {
     Seq,
 }
 
+impl NumberType {
+    /// Returns the radix the digits of the number are written in
+    pub fn radix(&self) -> u32 {
+        match self {
+            NumberType::Float | NumberType::Seq => 10,
+            NumberType::Hex | NumberType::HexFloat => 16,
+            NumberType::Binary => 2,
+            NumberType::Octal => 8,
+        }
+    }
+}
+
 /// Represents all possible token types that can be parsed by the tokenizer
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TokenType {
     /// Any alphanumeric string
     Word,
+    /// A word registered as a keyword, holding the id it was registered with
+    Keyword(usize),
     /// A numeric token, where [NumberType] specifies the format
     Number(NumberType),
+    /// A date, a time or both, in TOML shapes (e.g., `2024-05-01`, `12:30:00`,
+    /// `1979-05-27T07:32:00Z`), only produced when [TokenizerConfig::date_time_literals] is set
+    DateTime,
+    /// Numbers followed by units (e.g., `30s`, `1h30m`), only produced when
+    /// [TokenizerConfig::duration_units] is not empty
+    Duration,
     /// A sequence of characters surrounded by double quotes ("example")
     String,
     /// A single character surrounded by single quotes ('a')
@@ -35,12 +71,30 @@ pub enum TokenType {
     Symbol,
     /// A character recognized as an operator
     Operator,
+    /// A line or block comment, only emitted when [TokenizerConfig::emit_comments] is set
+    Comment,
+    /// A run of whitespace, only emitted when [TokenizerConfig::emit_whitespace] is set
+    Whitespace,
+    /// A line break (`\n` or `\r\n`), only emitted when [TokenizerConfig::emit_newlines] is set
+    Newline,
+    /// The opening quote of an interpolated string
+    StringStart,
+    /// A literal piece of an interpolated string, with escape sequences processed
+    StringFragment,
+    /// The delimiter opening an interpolation inside a string (e.g., `${`)
+    InterpolationStart,
+    /// The `}` closing an interpolation inside a string
+    InterpolationEnd,
+    /// The closing quote of an interpolated string
+    StringEnd,
+    /// A piece of invalid input, only produced by [Tokenizer::tokenize_recovering]
+    Error,
 }
 
 /// Represents the location of a token in the input text, with line and column values
 ///
 /// Format: Formats Loc as `<line+1>`:`<column+1>`.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Loc(
     /// Line number (0-based index)
     pub usize,
@@ -54,27 +108,238 @@ impl std::fmt::Display for Loc {
     }
 }
 
+/// Represents a position in the input text, between two characters
+///
+/// Format: Formats Pos as `<line+1>`:`<column+1>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct Pos {
+    /// Line number (0-based index)
+    pub line: usize,
+    /// Column number, in characters (0-based index)
+    pub column: usize,
+    /// Offset from the start of the input, in bytes
+    pub offset: usize,
+    /// Offset from the start of the input, in characters
+    pub char_offset: usize,
+}
+
+impl Pos {
+    /// Returns the line and column of the position
+    pub fn loc(&self) -> Loc {
+        Loc(self.line, self.column)
+    }
+}
+
+impl std::fmt::Display for Pos {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.loc().fmt(f)
+    }
+}
+
+/// Represents a range of the input text, usable to slice the original source
+///
+/// Format: Formats Span as `<start>-<end>`, both formatted as [Pos].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    /// Position of the first character (inclusive)
+    pub start: Pos,
+    /// Position past the last character (exclusive)
+    pub end: Pos,
+}
+
+impl Span {
+    /// Returns the span as a byte range, ready to index the source with
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.start.offset..self.end.offset
+    }
+
+    /// Length of the span in bytes
+    pub fn len(&self) -> usize {
+        self.end.offset - self.start.offset
+    }
+
+    /// Length of the span in characters
+    pub fn char_len(&self) -> usize {
+        self.end.char_offset - self.start.char_offset
+    }
+
+    /// Whether the span covers no character at all
+    pub fn is_empty(&self) -> bool {
+        self.start.offset == self.end.offset
+    }
+}
+
+impl std::fmt::Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
 /// Represents an individual token with type, value, and location
+///
+/// The value borrows from the source whenever possible; it only owns its text when the tokenizer
+/// had to rewrite it (escape sequences, digit separators, ...).
 #[derive(Debug, Clone)]
-pub struct Token {
+pub struct Token<'src> {
     /// The [TokenType] of the token
     pub r#type: TokenType,
-    /// The text of the token
-    pub value: Box<String>,
+    /// The text of the token. For numbers, this holds the digits only: radix prefixes and
+    /// digit separators are stripped, see [Token::lexeme] for the literal as written
+    pub value: Cow<'src, str>,
     /// The location of the token in the input
     pub loc: Loc,
+    /// The bytes of the input covered by the token, delimiters and prefixes included
+    pub span: Span,
+    /// The suffix glued to a number (e.g., `u8` in `10u8`), see
+    /// [TokenizerConfig::number_suffixes]
+    pub suffix: Option<Cow<'src, str>>,
+    /// How a [TokenType::String] token was written, [None] for other tokens
+    pub string: Option<StringInfo>,
+}
+
+/// Details on the form of a string literal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StringInfo {
+    /// Whether the string is a raw string, without escape processing (e.g., `r#"C:\path"#`)
+    pub raw: bool,
+    /// The bytes of the opening delimiter (e.g., `"`, `«`, `r#"`, `<<EOF`)
+    pub open: Span,
+    /// The bytes of the closing delimiter (e.g., `"`, `»`, `"#`, `EOF`)
+    pub close: Span,
+}
+
+impl<'src> Token<'src> {
+    /// Returns the original text of the token, as written in `src`
+    ///
+    /// `src` must be the input the token was produced from.
+    pub fn lexeme(&self, src: &'src str) -> &'src str {
+        &src[self.span.range()]
+    }
+
+    /// Returns the opening and closing delimiters of a string token, as written in `src`
+    ///
+    /// `src` must be the input the token was produced from.
+    pub fn delimiters(&self, src: &'src str) -> Option<(&'src str, &'src str)> {
+        self.string
+            .map(|info| (&src[info.open.range()], &src[info.close.range()]))
+    }
+
+    /// Detaches the token from the source by taking ownership of its value
+    pub fn into_owned(self) -> Token<'static> {
+        Token {
+            r#type: self.r#type,
+            value: Cow::Owned(self.value.into_owned()),
+            loc: self.loc,
+            span: self.span,
+            suffix: self.suffix.map(|suffix| Cow::Owned(suffix.into_owned())),
+            string: self.string,
+        }
+    }
+
+    /// Returns whether an integer token is negative, along with its digits and their radix
+    fn integer_digits(&self) -> Result<(bool, &str, u32), NumberError> {
+        match self.r#type {
+            TokenType::Number(NumberType::Float | NumberType::HexFloat) => {
+                Err(NumberError::NotAnInteger)
+            }
+            TokenType::Number(num_type) => {
+                let (negative, digits) = match self.value.strip_prefix('-') {
+                    Some(digits) => (true, digits),
+                    None => (false, self.value.strip_prefix('+').unwrap_or(&self.value)),
+                };
+                Ok((negative, digits, num_type.radix()))
+            }
+            _ => Err(NumberError::NotANumber),
+        }
+    }
+
+    /// Parses the absolute value of an integer token, along with whether it is negative
+    fn integer_magnitude(&self) -> Result<(bool, u128), NumberError> {
+        let (negative, digits, radix) = self.integer_digits()?;
+        let magnitude = u128::from_str_radix(digits, radix).map_err(|err| match err.kind() {
+            std::num::IntErrorKind::PosOverflow => NumberError::Overflow,
+            _ => NumberError::Invalid,
+        })?;
+        Ok((negative, magnitude))
+    }
+
+    /// Parses the value of an integer token as `u128`
+    pub fn as_u128(&self) -> Result<u128, NumberError> {
+        match self.integer_magnitude()? {
+            (true, magnitude) if magnitude != 0 => Err(NumberError::Overflow),
+            (_, magnitude) => Ok(magnitude),
+        }
+    }
+
+    /// Parses the value of an integer token as `u64`
+    pub fn as_u64(&self) -> Result<u64, NumberError> {
+        u64::try_from(self.as_u128()?).map_err(|_| NumberError::Overflow)
+    }
+
+    /// Parses the value of an integer token as `i128`
+    pub fn as_i128(&self) -> Result<i128, NumberError> {
+        match self.integer_magnitude()? {
+            (true, magnitude) => 0i128.checked_sub_unsigned(magnitude),
+            (false, magnitude) => i128::try_from(magnitude).ok(),
+        }
+        .ok_or(NumberError::Overflow)
+    }
+
+    /// Parses the value of an integer token as `i64`
+    pub fn as_i64(&self) -> Result<i64, NumberError> {
+        i64::try_from(self.as_i128()?).map_err(|_| NumberError::Overflow)
+    }
+
+    /// Parses the value of an integer token without any size limit.
+    ///
+    /// Negative numbers (other than `-0`) are reported as [NumberError::Overflow].
+    pub fn as_big(&self) -> Result<BigUint, NumberError> {
+        let (negative, digits, radix) = self.integer_digits()?;
+        let big = BigUint::from_str_radix(digits, radix)?;
+        if negative && !big.is_zero() {
+            return Err(NumberError::Overflow);
+        }
+        Ok(big)
+    }
+
+    /// Parses the value of any number token as `f64`, rounding to the nearest representable value
+    pub fn as_f64(&self) -> Result<f64, NumberError> {
+        match self.r#type {
+            TokenType::Number(NumberType::Float) => {
+                self.value.parse().map_err(|_| NumberError::Invalid)
+            }
+            TokenType::Number(NumberType::HexFloat) => num::parse_hex_float(&self.value),
+            _ => {
+                let (negative, digits, radix) = self.integer_digits()?;
+                let magnitude = BigUint::from_str_radix(digits, radix)?.to_f64();
+                Ok(if negative { -magnitude } else { magnitude })
+            }
+        }
+    }
 }
 
 /// Primary struct for tokenizing an input string, with methods for parsing and generating tokens
-pub struct Tokenizer {
-    pub lines: Vec<Vec<char>>,
+///
+/// The tokenizer never copies the input; produced [Token]s borrow from it.
+pub struct Tokenizer<'src> {
+    src: &'src str,
+    pos: usize,
     ln: usize,
     col: usize,
+    chars: usize,
     config: TokenizerConfig,
+    operators: Trie,
+    keywords: HashMap<String, usize>,
+    modes: Vec<Mode>,
+    token_start: Pos,
+    /// Whether the previous token that is not blank space or a comment expects an operand after
+    /// it, and its last line
+    prev: Option<(bool, usize)>,
+    failed: bool,
 }
 
 /// Configurable option for specific settings in [TokenizerConfig]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub enum Choice<T>
 where
     T: Copy + Clone,
@@ -82,15 +347,152 @@ where
     /// An active choice with a specified value of type T
     Yes(T),
     /// No active choice
+    #[default]
     No,
 }
 
-impl<T> Default for Choice<T>
-where
-    T: Copy + Clone,
-{
-    fn default() -> Self {
-        Self::No
+/// Decides what happens to unknown escape sequences in strings and characters (e.g., `\q`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EscapePolicy {
+    /// Raises a [TokenizationError::InvalidEscape]
+    Error,
+    /// Keeps the sequence as written, backslash included
+    #[default]
+    KeepRaw,
+    /// Drops the backslash and keeps the escaped character
+    PassThrough,
+}
+
+/// Decides where the digit separator (see [TokenizerConfig::allow_digit_separator]) may appear
+/// besides between two digits
+///
+/// The default is [DigitSeparatorPolicy::strict], which also matches C++ (with `'` as the
+/// separator).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DigitSeparatorPolicy {
+    /// Before the first digit of an exponent (e.g., `1e_5`)
+    pub leading: bool,
+    /// After the last digit of a part of the number (e.g., `1_`, `1_e5`, `0xff_`)
+    pub trailing: bool,
+    /// Several separators in a row (e.g., `1__000`)
+    pub consecutive: bool,
+    /// Right before or after the decimal point (e.g., `1_.5`, `1._5`)
+    pub adjacent_to_dot: bool,
+    /// Right after a radix prefix (e.g., `0x_ff`)
+    pub after_radix_prefix: bool,
+}
+
+impl DigitSeparatorPolicy {
+    /// Separators are only allowed between two digits, one at a time
+    pub const fn strict() -> Self {
+        Self {
+            leading: false,
+            trailing: false,
+            consecutive: false,
+            adjacent_to_dot: false,
+            after_radix_prefix: false,
+        }
+    }
+
+    /// Rust rules: anywhere after the first digit or the radix prefix, except next to the dot
+    pub const fn rust() -> Self {
+        Self {
+            leading: true,
+            trailing: true,
+            consecutive: true,
+            adjacent_to_dot: false,
+            after_radix_prefix: true,
+        }
+    }
+
+    /// Java rules: only between digits, several in a row being allowed
+    pub const fn java() -> Self {
+        Self {
+            consecutive: true,
+            ..Self::strict()
+        }
+    }
+
+    /// Python rules: only between digits, one at a time, or right after a radix prefix
+    pub const fn python() -> Self {
+        Self {
+            after_radix_prefix: true,
+            ..Self::strict()
+        }
+    }
+}
+
+/// Rule of a [DigitSeparatorPolicy] broken by a digit separator, carried by
+/// [TokenizationError::UnexpectedDigitSeparator]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigitSeparatorRule {
+    /// [DigitSeparatorPolicy::leading] (e.g., `1e_5`)
+    Leading,
+    /// [DigitSeparatorPolicy::trailing] (e.g., `1_`)
+    Trailing,
+    /// [DigitSeparatorPolicy::consecutive] (e.g., `1__000`)
+    Consecutive,
+    /// [DigitSeparatorPolicy::adjacent_to_dot] (e.g., `1_.5`)
+    AdjacentToDot,
+    /// [DigitSeparatorPolicy::after_radix_prefix] (e.g., `0x_ff`)
+    AfterRadixPrefix,
+    /// Separators must come before a digit, whatever the policy (e.g., `0x_`)
+    NotFollowedByDigit,
+}
+
+/// Decides when a `+` or `-` directly followed by a number is part of it (e.g., `-42`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SignedNumbers {
+    /// Signs are always separate tokens, as in expression languages
+    #[default]
+    Never,
+    /// Signs are always part of the number, as in data formats
+    Always,
+    /// Signs are part of the number at the start of a line or after an operator, a symbol other
+    /// than a closing `)`, `]` or `}`, or the start of an interpolation, so that `a - 1`, `a-1`
+    /// and `(a)-1` keep their operator
+    AfterOperator,
+}
+
+/// Decides which characters make up a [TokenType::Word]
+#[derive(Clone, Default)]
+pub enum IdentifierPolicy {
+    /// Any run of characters that are not whitespace, symbols, operators or comments
+    #[default]
+    Permissive,
+    /// Unicode identifiers: a `XID_Start` character or `_`, followed by `XID_Continue`
+    /// characters
+    UnicodeXid,
+    /// ASCII identifiers: a letter or `_`, followed by letters, digits or `_`
+    Ascii,
+    /// A predicate called with each character and whether it is the first of the word
+    Custom(Arc<dyn Fn(char, bool) -> bool + Send + Sync>),
+}
+
+impl IdentifierPolicy {
+    /// Whether `c` may appear in an identifier, `first` telling if it would start it.
+    ///
+    /// Always `true` for [IdentifierPolicy::Permissive].
+    pub fn accepts(&self, c: char, first: bool) -> bool {
+        match self {
+            IdentifierPolicy::Permissive => true,
+            IdentifierPolicy::UnicodeXid if first => c == '_' || unicode_ident::is_xid_start(c),
+            IdentifierPolicy::UnicodeXid => unicode_ident::is_xid_continue(c),
+            IdentifierPolicy::Ascii if first => c == '_' || c.is_ascii_alphabetic(),
+            IdentifierPolicy::Ascii => c == '_' || c.is_ascii_alphanumeric(),
+            IdentifierPolicy::Custom(predicate) => predicate(c, first),
+        }
+    }
+}
+
+impl std::fmt::Debug for IdentifierPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IdentifierPolicy::Permissive => write!(f, "Permissive"),
+            IdentifierPolicy::UnicodeXid => write!(f, "UnicodeXid"),
+            IdentifierPolicy::Ascii => write!(f, "Ascii"),
+            IdentifierPolicy::Custom(_) => write!(f, "Custom(..)"),
+        }
     }
 }
 
@@ -104,10 +506,84 @@ pub struct TokenizerConfig {
     pub ignore_numbers: bool,
     /// Allows a specific character as a digit separator (e.g., `_`)
     pub allow_digit_separator: Choice<char>,
+    /// Where the digit separator may appear besides between two digits
+    pub digit_separators: DigitSeparatorPolicy,
     /// List of characters to be treated as symbols
     pub consider_as_symbols: Vec<char>,
     /// List of characters to be treated as operators
     pub consider_as_operators: Vec<char>,
+    /// List of multi-character operators (e.g., `==`, `->`), matched by longest match along with
+    /// [TokenizerConfig::consider_as_operators]
+    pub consider_as_operator_strs: Vec<String>,
+    /// Emits runs of whitespace as [TokenType::Whitespace] tokens instead of skipping them
+    pub emit_whitespace: bool,
+    /// Emits line breaks as [TokenType::Newline] tokens instead of skipping them
+    pub emit_newlines: bool,
+    /// Additional pairs of delimiters enclosing strings (e.g., `«` and `»`), on top of `"`
+    pub string_delimiters: Vec<(String, String)>,
+    /// Whether string literals may contain line breaks
+    pub allow_multiline_strings: bool,
+    /// Whether floats may start with their decimal point (e.g., `.5`)
+    pub allow_leading_dot: bool,
+    /// Whether floats may end with their decimal point (e.g., `1.`)
+    pub allow_trailing_dot: bool,
+    /// Never lets a number start or end on a dot that is part of `..`, so that `1..2` lexes as
+    /// `1`, `.`, `.`, `2` (or `1`, `..`, `2` with `..` registered as an operator)
+    pub protect_ranges: bool,
+    /// Recognizes `inf`, `infinity` and `nan`, in any case, as [NumberType::Float] numbers
+    pub special_floats: bool,
+    /// Recognizes hexadecimal floats (e.g., `0x1.8p3`, `0x1p-2`) as [NumberType::HexFloat]
+    pub hex_floats: bool,
+    /// What to do with unknown escape sequences
+    pub unknown_escapes: EscapePolicy,
+    /// Recognizes strings enclosed by `"""` or `\'\'\'`, which may always span multiple lines
+    pub triple_quoted_strings: bool,
+    /// Recognizes heredocs: `<<TAG` at the end of a line opens a string running until a line
+    /// made of `TAG` only
+    pub heredocs: bool,
+    /// Delimiter opening interpolations in `"` strings (e.g., `${`), closed by the matching `}`
+    pub string_interpolation: Option<String>,
+    /// Recognizes backtick template literals, always interpolated with
+    /// [TokenizerConfig::string_interpolation] (`${` when unset)
+    pub template_literals: bool,
+    /// Prefixes opening a raw string (e.g., `r` for `r"..."`), where escapes are not processed
+    pub raw_string_prefixes: Vec<String>,
+    /// Maximum number of `#` allowed around a raw string (e.g., 1 for `r#"..."#`)
+    pub raw_string_max_hashes: usize,
+    /// Suffixes allowed right after a number (e.g., `u8`, `f32`, `n`)
+    pub number_suffixes: Vec<String>,
+    /// When a sign before a number is part of the [TokenType::Number] token
+    pub signed_numbers: SignedNumbers,
+    /// Recognizes dates and times as [TokenType::DateTime] tokens, unless
+    /// [TokenizerConfig::ignore_numbers] is set
+    pub date_time_literals: bool,
+    /// Units making a number a [TokenType::Duration] (e.g., `ms`, `s`, `m`, `h`), ignored when
+    /// [TokenizerConfig::ignore_numbers] is set
+    pub duration_units: Vec<String>,
+    /// Characters allowed in words; anything else that fits no other category is an error,
+    /// unless the policy is [IdentifierPolicy::Permissive]
+    pub identifier_policy: IdentifierPolicy,
+    /// Words to be treated as keywords, along with their id
+    pub keywords: Vec<(String, usize)>,
+    /// Matches keywords regardless of their case
+    pub keywords_case_insensitive: bool,
+    /// Delimiters opening a comment that runs until the end of the line (e.g., `//`)
+    pub line_comments: Vec<String>,
+    /// Delimiter pairs enclosing block comments (e.g., `/*` and `*/`)
+    pub block_comments: Vec<BlockComment>,
+    /// Emits comments as [TokenType::Comment] tokens instead of skipping them
+    pub emit_comments: bool,
+}
+
+/// Delimiters of a block comment, see [TokenizerConfig::block_comments]
+#[derive(Clone, Debug)]
+pub struct BlockComment {
+    /// Delimiter opening the comment (e.g., `/*`)
+    pub open: String,
+    /// Delimiter closing the comment (e.g., `*/`)
+    pub close: String,
+    /// Whether an `open` delimiter inside the comment opens a nested comment
+    pub nested: bool,
 }
 
 /// A builder struct for creating a [TokenizerConfig] instance with customized options
@@ -116,12 +592,21 @@ pub struct TokenizerBuilder {
     conf: TokenizerConfig,
 }
 
+impl Default for TokenizerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TokenizerBuilder {
     /// Creates a default [TokenizerBuilder]
     pub fn new() -> TokenizerBuilder {
         TokenizerBuilder {
             conf: TokenizerConfig {
                 consider_as_symbols: vec!['.'],
+                allow_multiline_strings: true,
+                allow_leading_dot: true,
+                allow_trailing_dot: true,
                 ..Default::default()
             },
         }
@@ -143,6 +628,80 @@ impl TokenizerBuilder {
         lb
     }
 
+    /// Configures whether whitespace is emitted as tokens or skipped
+    pub fn emit_whitespace(self, set_to: bool) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.emit_whitespace = set_to;
+        lb
+    }
+
+    /// Configures whether line breaks are emitted as tokens or skipped
+    pub fn emit_newlines(self, set_to: bool) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.emit_newlines = set_to;
+        lb
+    }
+
+    /// Adds a pair of delimiters enclosing strings
+    pub fn add_string_delimiter(self, open: &str, close: &str) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf
+            .string_delimiters
+            .push((open.to_string(), close.to_string()));
+        lb
+    }
+
+    /// Configures whether strings may span multiple lines
+    pub fn allow_multiline_strings(self, set_to: bool) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.allow_multiline_strings = set_to;
+        lb
+    }
+
+    /// Configures whether floats may start with their decimal point (e.g., `.5`)
+    pub fn allow_leading_dot(self, set_to: bool) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.allow_leading_dot = set_to;
+        lb
+    }
+
+    /// Configures whether floats may end with their decimal point (e.g., `1.`)
+    pub fn allow_trailing_dot(self, set_to: bool) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.allow_trailing_dot = set_to;
+        lb
+    }
+
+    /// Configures whether numbers stay clear of `..`, keeping ranges such as `1..2` intact
+    pub fn protect_ranges(self, set_to: bool) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.protect_ranges = set_to;
+        lb
+    }
+
+    /// Configures whether `inf`, `infinity` and `nan` are recognized as floats
+    pub fn special_floats(self, set_to: bool) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.special_floats = set_to;
+        lb
+    }
+
+    /// Configures whether hexadecimal floats (e.g., `0x1.8p3`) are recognized
+    pub fn hex_floats(self, set_to: bool) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.hex_floats = set_to;
+        lb
+    }
+
     /// Sets the digit separator
     pub fn allow_digit_separator(self, choice: Choice<char>) -> Self {
         let mut lb = TokenizerBuilder::new();
@@ -151,6 +710,14 @@ impl TokenizerBuilder {
         lb
     }
 
+    /// Sets where the digit separator may appear besides between two digits
+    pub fn digit_separator_policy(self, policy: DigitSeparatorPolicy) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.digit_separators = policy;
+        lb
+    }
+
     /// Adds a symbol character
     pub fn add_symbol(self, sym: char) -> Self {
         let mut lb = TokenizerBuilder::new();
@@ -183,521 +750,2037 @@ impl TokenizerBuilder {
         lb
     }
 
-    /// Constructs a [Tokenizer] with the specified input and configuration.
-    pub fn build<T>(self, with_input: T) -> Tokenizer
-    where
-        T: ToString,
-    {
-        Tokenizer::new(with_input, self.conf)
+    /// Adds an operator made of one or more characters
+    pub fn add_operator_str(self, op: &str) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.consider_as_operator_strs.push(op.to_string());
+        lb
     }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum OutOfBound {
-    Empty,
-    Out,
-    Within,
-}
-
-impl Tokenizer {
-    /// Creates a TokenizerBuilder instance for configuring and initializing the tokenizer
-    pub fn builder() -> TokenizerBuilder {
-        TokenizerBuilder::new()
-    }
-    /// Initializes the tokenizer with input text and a configuration
-    pub fn new<T>(input: T, config: TokenizerConfig) -> Self
-    where
-        T: ToString,
-    {
-        let input = input.to_string();
-        Self {
-            lines: input.lines().map(|line| line.chars().collect()).collect(),
-            ln: 0,
-            col: 0,
-            config,
-        }
+    /// Adds multiple operators made of one or more characters
+    pub fn add_operators_str(self, ops: &[&str]) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf
+            .consider_as_operator_strs
+            .extend(ops.iter().map(|op| op.to_string()));
+        lb
     }
 
-    fn is_out_of_bound(&self) -> OutOfBound {
-        if self.ln >= self.lines.len() {
-            return OutOfBound::Out;
-        } else if !self.lines[self.ln].is_empty() {
-            if self.col >= self.lines[self.ln].len() {
-                return OutOfBound::Out;
-            }
-        } else {
-            return OutOfBound::Empty;
-        }
-
-        OutOfBound::Within
+    /// Sets the handling of unknown escape sequences
+    pub fn unknown_escapes(self, policy: EscapePolicy) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.unknown_escapes = policy;
+        lb
     }
 
-    fn is_out_of_bound_for(&self, ln: usize, col: usize) -> OutOfBound {
-        if ln >= self.lines.len() {
-            return OutOfBound::Out;
-        } else if !self.lines[ln].is_empty() {
-            if col >= self.lines[self.ln].len() {
-                return OutOfBound::Out;
-            }
-        } else {
-            return OutOfBound::Empty;
-        }
-
-        OutOfBound::Within
+    /// Sets when signs are part of number tokens
+    pub fn signed_numbers(self, policy: SignedNumbers) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.signed_numbers = policy;
+        lb
     }
 
-    #[track_caller]
-    fn consume(&mut self, len: usize) {
-        let mut len = len;
-        let ln_max = self.lines.len();
-
-        while len > 0 {
-            if self.ln + 1 > ln_max {
-                // At this point, calling consume will have no effect,
-                // The cursor is already out of bound
-                // NOTE: don't put `unreachable!()` to avoid panic
-                if self.is_out_of_bound() == OutOfBound::Out {
-                    let caller_location = std::panic::Location::caller();
-                    eprintln!(
-                        "WARNING at {} The consume function is called but we are out of characters",
-                        caller_location
-                    );
-                    break;
-                }
-
-                self.ln += 1;
-            } else {
-                let col_max = self.lines[self.ln].len();
-                if self.col + 1 >= col_max {
-                    self.next_line();
-                } else {
-                    self.col += 1;
-                }
-            }
-
-            len -= 1;
-        }
+    /// Configures whether triple-quoted strings are recognized
+    pub fn triple_quoted_strings(self, set_to: bool) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.triple_quoted_strings = set_to;
+        lb
     }
 
-    fn fake_consume(&self, len: usize) -> (OutOfBound, Loc) {
-        let mut len = len;
-        let ln_max = self.lines.len();
-
-        let mut ln = self.ln;
-        let mut col = self.col;
+    /// Configures whether heredocs are recognized
+    pub fn heredocs(self, set_to: bool) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.heredocs = set_to;
+        lb
+    }
 
-        while len > 0 {
-            if ln + 1 > ln_max {
-                ln += 1;
-            } else {
-                let col_max = self.lines[ln].len();
-                if col + 1 >= col_max {
-                    ln += 1;
-                    col = 0;
-                } else {
-                    col += 1;
-                }
-            }
+    /// Enables interpolation in strings, opened by `open` and closed by the matching `}`
+    pub fn string_interpolation(self, open: &str) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.string_interpolation = Some(open.to_string());
+        lb
+    }
 
-            len -= 1;
-        }
+    /// Configures whether backtick template literals are recognized
+    pub fn template_literals(self, set_to: bool) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.template_literals = set_to;
+        lb
+    }
 
-        (self.is_out_of_bound_for(ln, col), Loc(ln, col))
+    /// Enables raw strings opened by one of `prefixes`, fenced by up to `max_hashes` hashes
+    pub fn raw_strings(self, prefixes: &[&str], max_hashes: usize) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf
+            .raw_string_prefixes
+            .extend(prefixes.iter().map(|prefix| prefix.to_string()));
+        lb.conf.raw_string_max_hashes = max_hashes;
+        lb
     }
 
-    #[inline]
-    fn next_line(&mut self) {
-        self.ln += 1;
-        self.col = 0;
+    /// Adds a suffix allowed right after a number
+    pub fn add_number_suffix(self, suffix: &str) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.number_suffixes.push(suffix.to_string());
+        lb
     }
 
-    fn get_next_char(&self) -> Option<&char> {
-        if self.is_out_of_bound() == OutOfBound::Within {
-            Some(&self.lines[self.ln][self.col])
-        } else {
-            None
-        }
+    /// Adds multiple suffixes allowed right after a number
+    pub fn add_number_suffixes(self, suffixes: &[&str]) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf
+            .number_suffixes
+            .extend(suffixes.iter().map(|suffix| suffix.to_string()));
+        lb
     }
 
-    fn peek_tok(&self) -> Option<&char> {
-        let (bound, loc) = self.fake_consume(1);
-        match bound {
-            OutOfBound::Empty => Some(&'\n'),
-            OutOfBound::Out => None,
-            OutOfBound::Within => Some(&self.lines[loc.0][loc.1]),
-        }
+    /// Configures whether dates and times (e.g., `2024-05-01`, `12:30:00`) are recognized
+    pub fn date_time_literals(self, set_to: bool) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.date_time_literals = set_to;
+        lb
     }
 
-    fn parse_word(&mut self) -> Result<Token, TokenizationError> {
-        let mut word = String::new();
-        let start_ln = self.ln;
-        let start_col = self.col;
-        while let Some(c) = self.get_next_char() {
-            if *c != ' ' {
-                if !self.config.consider_as_symbols.contains(c)
-                    && !self.config.consider_as_operators.contains(c)
-                {
-                    word.push(*c);
+    /// Adds multiple units making a number a duration (e.g., `s` for `30s`)
+    pub fn add_duration_units(self, units: &[&str]) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf
+            .duration_units
+            .extend(units.iter().map(|unit| unit.to_string()));
+        lb
+    }
+
+    /// Sets the characters allowed in words
+    pub fn identifier_policy(self, policy: IdentifierPolicy) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.identifier_policy = policy;
+        lb
+    }
+
+    /// Sets the characters allowed in words with a predicate, called with each character and
+    /// whether it is the first of the word
+    pub fn identifier_predicate<F>(self, predicate: F) -> Self
+    where
+        F: Fn(char, bool) -> bool + Send + Sync + 'static,
+    {
+        self.identifier_policy(IdentifierPolicy::Custom(Arc::new(predicate)))
+    }
+
+    /// Adds a keyword, identified by the id following the largest one already registered (its
+    /// position in the keyword table, unless [TokenizerBuilder::add_keyword_with_id] was used)
+    pub fn add_keyword(self, kw: &str) -> Self {
+        let id = self
+            .conf
+            .keywords
+            .iter()
+            .map(|(_, id)| id + 1)
+            .max()
+            .unwrap_or_default();
+        self.add_keyword_with_id(kw, id)
+    }
+
+    /// Adds multiple keywords, identified by consecutive ids as with
+    /// [TokenizerBuilder::add_keyword]
+    pub fn add_keywords(self, kws: &[&str]) -> Self {
+        kws.iter().fold(self, |lb, kw| lb.add_keyword(kw))
+    }
+
+    /// Adds a keyword with a user-supplied id, e.g. the discriminant of a keyword enum
+    pub fn add_keyword_with_id(self, kw: &str, id: usize) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.keywords.push((kw.to_string(), id));
+        lb
+    }
+
+    /// Configures whether keywords are matched regardless of their case
+    pub fn keywords_case_insensitive(self, set_to: bool) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.keywords_case_insensitive = set_to;
+        lb
+    }
+
+    /// Adds a delimiter opening a comment until the end of the line
+    pub fn line_comment(self, open: &str) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.line_comments.push(open.to_string());
+        lb
+    }
+
+    /// Adds a pair of delimiters enclosing a block comment
+    pub fn block_comment(self, open: &str, close: &str) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.block_comments.push(BlockComment {
+            open: open.to_string(),
+            close: close.to_string(),
+            nested: false,
+        });
+        lb
+    }
+
+    /// Adds a pair of delimiters enclosing a block comment that can be nested
+    pub fn nested_block_comment(self, open: &str, close: &str) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.block_comments.push(BlockComment {
+            open: open.to_string(),
+            close: close.to_string(),
+            nested: true,
+        });
+        lb
+    }
+
+    /// Configures whether comments are emitted as tokens or skipped
+    pub fn emit_comments(self, set_to: bool) -> Self {
+        let mut lb = TokenizerBuilder::new();
+        lb.conf = self.conf;
+        lb.conf.emit_comments = set_to;
+        lb
+    }
+
+    /// Constructs a [Tokenizer] with the specified input and configuration.
+    pub fn build(self, with_input: &str) -> Tokenizer<'_> {
+        Tokenizer::new(with_input, self.conf)
+    }
+}
+
+/// Kind of comment starting at the cursor, indexing into the configured delimiters
+#[derive(Debug, Clone, Copy)]
+enum CommentStart {
+    Line(usize),
+    Block(usize),
+}
+
+/// Nested lexing context, entered through interpolated strings
+#[derive(Debug, Clone, Copy)]
+enum Mode {
+    /// Inside an interpolated string closed by `close`, opened at `start`
+    String {
+        close: char,
+        multiline: bool,
+        start: Pos,
+    },
+    /// Inside an interpolation, with the number of `{` left to close
+    Interpolation { depth: usize },
+}
+
+impl<'src> Tokenizer<'src> {
+    /// Creates a TokenizerBuilder instance for configuring and initializing the tokenizer
+    pub fn builder() -> TokenizerBuilder {
+        TokenizerBuilder::new()
+    }
+    /// Initializes the tokenizer with input text and a configuration
+    pub fn new(input: &'src str, config: TokenizerConfig) -> Self {
+        let mut operators = Trie::default();
+        for op in &config.consider_as_operators {
+            operators.insert(op.encode_utf8(&mut [0; 4]));
+        }
+        for op in &config.consider_as_operator_strs {
+            operators.insert(op);
+        }
+        let keywords = config
+            .keywords
+            .iter()
+            .map(|(kw, id)| {
+                if config.keywords_case_insensitive {
+                    (kw.to_lowercase(), *id)
                 } else {
-                    break;
+                    (kw.clone(), *id)
                 }
-            } else {
-                break;
-            }
-            self.consume(1);
+            })
+            .collect();
+
+        Self {
+            src: input,
+            pos: 0,
+            ln: 0,
+            col: 0,
+            chars: 0,
+            config,
+            operators,
+            keywords,
+            modes: vec![],
+            token_start: Pos::default(),
+            prev: None,
+            failed: false,
         }
+    }
 
-        Ok(Token {
-            r#type: TokenType::Word,
-            value: Box::new(word),
-            loc: Loc(start_ln, start_col),
-        })
+    /// Returns the input being tokenized
+    pub fn source(&self) -> &'src str {
+        self.src
     }
 
-    fn parse_float(&mut self) -> Result<Token, TokenizationError> {
-        let mut float = String::new();
-        let start_ln = self.ln;
-        let start_col = self.col;
-        let mut encountered_dot = false;
+    #[inline]
+    fn rest(&self) -> &'src str {
+        &self.src[self.pos..]
+    }
 
-        if *self.get_next_char().unwrap() == '.' {
-            float.push_str("0.");
-            encountered_dot = true;
-            self.consume(1);
+    #[inline]
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    #[inline]
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.rest().chars().nth(n)
+    }
+
+    /// Consumes a single character, keeping track of lines and columns
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        self.chars += 1;
+        if c == '\n' {
+            self.ln += 1;
+            self.col = 0;
+        } else {
+            self.col += 1;
         }
+        Some(c)
+    }
 
-        while let Some(c) = self.get_next_char() {
-            if c.is_ascii_digit() {
-                float.push(*c);
-            } else if *c == '.' {
-                if encountered_dot {
-                    break;
-                } else {
-                    float.push('.');
-                    encountered_dot = true;
-                }
-            } else {
-                break;
-            }
-            self.consume(1);
+    fn consume(&mut self, len: usize) {
+        for _ in 0..len {
+            self.bump();
         }
+    }
 
-        Ok(Token {
-            r#type: TokenType::Number(NumberType::Float),
-            value: Box::new(float),
-            loc: Loc(start_ln, start_col),
-        })
+    /// Whether the cursor sits on a line break (`\n` or `\r\n`)
+    fn at_line_break(&self) -> bool {
+        let rest = self.rest();
+        rest.starts_with('\n') || rest.starts_with("\r\n")
     }
 
-    fn parse_number(&mut self) -> Result<Token, TokenizationError> {
-        let mut num_type = NumberType::Seq;
-        let mut parsing_float = false;
-        let mut num = String::new();
-
-        let start_ln = self.ln;
-        let start_col = self.col;
-        let mut inner_col = self.col;
-
-        while let Some(c) = self.get_next_char() {
-            if c.is_ascii_digit() {
-                num.push(*c);
-            } else if *c == '.' {
-                if parsing_float {
-                    break;
-                } else {
-                    parsing_float = true;
-                    num.push('.');
-                    num_type = NumberType::Float
+    #[inline]
+    fn mark(&self) -> Pos {
+        Pos {
+            line: self.ln,
+            column: self.col,
+            offset: self.pos,
+            char_offset: self.chars,
+        }
+    }
+
+    #[inline]
+    fn span_from(&self, start: Pos) -> Span {
+        Span {
+            start,
+            end: self.mark(),
+        }
+    }
+
+    #[inline]
+    fn slice_from(&self, start: Pos) -> &'src str {
+        &self.src[start.offset..self.pos]
+    }
+
+    fn token(&self, r#type: TokenType, value: Cow<'src, str>, start: Pos) -> Token<'src> {
+        Token {
+            r#type,
+            value,
+            loc: start.loc(),
+            span: self.span_from(start),
+            suffix: None,
+            string: None,
+        }
+    }
+
+    /// Consumes `len` characters and turns them into a token of the given type
+    fn single(&mut self, r#type: TokenType, len: usize) -> Token<'src> {
+        let start = self.mark();
+        self.consume(len);
+        self.token(r#type, Cow::Borrowed(self.slice_from(start)), start)
+    }
+
+    /// Finds the longest comment delimiter starting at the cursor
+    fn comment_start(&self) -> Option<CommentStart> {
+        let rest = self.rest();
+        let line = self
+            .config
+            .line_comments
+            .iter()
+            .enumerate()
+            .filter(|(_, open)| !open.is_empty() && rest.starts_with(open.as_str()))
+            .map(|(i, open)| (open.len(), CommentStart::Line(i)));
+        let block = self
+            .config
+            .block_comments
+            .iter()
+            .enumerate()
+            .filter(|(_, block)| !block.open.is_empty() && rest.starts_with(block.open.as_str()))
+            .map(|(i, block)| (block.open.len(), CommentStart::Block(i)));
+
+        line.chain(block)
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, kind)| kind)
+    }
+
+    fn parse_comment(&mut self, kind: CommentStart) -> Result<Token<'src>, TokenizationError> {
+        let start = self.mark();
+        let value = match kind {
+            CommentStart::Line(i) => {
+                self.consume(self.config.line_comments[i].chars().count());
+                let content_start = self.pos;
+                while self.peek().is_some() && !self.at_line_break() {
+                    self.bump();
                 }
-            } else if let Choice::Yes(with) = &self.config.allow_digit_separator {
-                if *c == *with {
-                    self.consume(1);
-                    if let Some(next_char) = self.get_next_char() {
-                        if !next_char.is_ascii_digit() {
-                            return Err(TokenizationError::UnexpectedDigitSeparator(Loc(
-                                start_ln, inner_col,
-                            )));
-                        } else {
-                            num.push(*next_char);
+                &self.src[content_start..self.pos]
+            }
+            CommentStart::Block(i) => {
+                let BlockComment {
+                    open,
+                    close,
+                    nested,
+                } = self.config.block_comments[i].clone();
+                self.consume(open.chars().count());
+                let content_start = self.pos;
+                let mut depth = 1usize;
+                loop {
+                    let rest = self.rest();
+                    if rest.is_empty() {
+                        return Err(TokenizationError::UnterminatedComment(
+                            self.span_from(start),
+                        ));
+                    } else if !close.is_empty() && rest.starts_with(close.as_str()) {
+                        depth -= 1;
+                        if depth == 0 {
+                            let content_end = self.pos;
+                            self.consume(close.chars().count());
+                            break &self.src[content_start..content_end];
                         }
+                        self.consume(close.chars().count());
+                    } else if nested && rest.starts_with(open.as_str()) {
+                        depth += 1;
+                        self.consume(open.chars().count());
                     } else {
-                        return Err(TokenizationError::UnexpectedDigitSeparator(Loc(
-                            start_ln, inner_col,
-                        )));
+                        self.bump();
                     }
-                } else {
+                }
+            }
+        };
+
+        Ok(self.token(TokenType::Comment, Cow::Borrowed(value), start))
+    }
+
+    /// Length, in characters, of the longest operator starting at the cursor
+    #[inline]
+    fn operator_len(&self) -> Option<usize> {
+        self.operators.longest_match(self.rest())
+    }
+
+    /// Whether a word may start with `c`
+    fn is_word_start(&self, c: char) -> bool {
+        (self.config.ignore_numbers && c.is_ascii_digit())
+            || self.config.identifier_policy.accepts(c, true)
+    }
+
+    fn parse_word(&mut self) -> Result<Token<'src>, TokenizationError> {
+        let start = self.mark();
+        if matches!(self.config.identifier_policy, IdentifierPolicy::Permissive) {
+            while let Some(c) = self.peek() {
+                if c.is_whitespace()
+                    || self.config.consider_as_symbols.contains(&c)
+                    || self.operator_len().is_some()
+                    || self.comment_start().is_some()
+                {
                     break;
                 }
-            } else {
-                break;
+                if matches!(c, '{' | '}') && !self.modes.is_empty() {
+                    // Braces are tracked by interpolations, a leading one is a word of its own
+                    if self.pos == start.offset {
+                        self.bump();
+                    }
+                    break;
+                }
+                self.bump();
+            }
+        } else {
+            // The first character was checked by `is_word_start`
+            self.bump();
+            while self
+                .peek()
+                .is_some_and(|c| self.config.identifier_policy.accepts(c, false))
+            {
+                self.bump();
             }
-            inner_col += 1;
-            self.consume(1);
         }
 
-        Ok(Token {
-            r#type: TokenType::Number(num_type),
-            value: Box::new(num),
-            loc: Loc(start_ln, start_col),
-        })
+        let word = self.slice_from(start);
+        let keyword = if self.config.keywords_case_insensitive {
+            self.keywords.get(&word.to_lowercase())
+        } else {
+            self.keywords.get(word)
+        };
+        let r#type = match keyword {
+            Some(id) => TokenType::Keyword(*id),
+            None => TokenType::Word,
+        };
+
+        Ok(self.token(r#type, Cow::Borrowed(word), start))
     }
 
-    fn parse_binary(&mut self) -> Result<Token, TokenizationError> {
-        let mut num = String::new();
+    /// Consumes a run of digits satisfying `is_digit`, possibly split by the digit separator
+    /// according to [TokenizerConfig::digit_separators]. `leading` tells whether the run may
+    /// start with separators, which otherwise breaks `start_rule`.
+    ///
+    /// Returns whether a digit separator was encountered.
+    fn parse_digits(
+        &mut self,
+        is_digit: fn(&char) -> bool,
+        leading: bool,
+        start_rule: DigitSeparatorRule,
+    ) -> Result<bool, TokenizationError> {
+        let policy = self.config.digit_separators;
+        let mut separated = false;
+        let mut first = true;
+        while let Some(c) = self.peek() {
+            match self.config.allow_digit_separator {
+                Choice::Yes(with) if c == with => {
+                    let start = self.mark();
+                    while self.peek() == Some(with) {
+                        self.bump();
+                    }
+                    let next = self.peek();
+                    let before_digit = next.is_some_and(|next| is_digit(&next));
+                    let broken = if self.pos - start.offset > with.len_utf8() && !policy.consecutive
+                    {
+                        Some(DigitSeparatorRule::Consecutive)
+                    } else if first && !leading {
+                        Some(start_rule)
+                    } else if first && !before_digit {
+                        Some(DigitSeparatorRule::NotFollowedByDigit)
+                    } else if next == Some('.') {
+                        (!policy.adjacent_to_dot).then_some(DigitSeparatorRule::AdjacentToDot)
+                    } else {
+                        (!before_digit && !policy.trailing).then_some(DigitSeparatorRule::Trailing)
+                    };
+                    if let Some(rule) = broken {
+                        return Err(TokenizationError::UnexpectedDigitSeparator(
+                            self.span_from(start),
+                            rule,
+                        ));
+                    }
+                    separated = true;
+                }
+                _ if is_digit(&c) => {
+                    self.bump();
+                }
+                _ => break,
+            }
+            first = false;
+        }
+        Ok(separated)
+    }
 
-        let start_ln = self.ln;
-        let start_col = self.col;
-        self.consume(2);
+    /// Consumes an exponent (`e10`, `E-3`, ...) if the cursor sits on one.
+    ///
+    /// Returns whether an exponent was found and whether a digit separator was encountered.
+    fn parse_exponent(&mut self) -> Result<(bool, bool), TokenizationError> {
+        if !matches!(self.peek(), Some('e' | 'E')) {
+            return Ok((false, false));
+        }
 
-        while let Some(c) = self.get_next_char() {
-            if matches!(*c, '1' | '0') {
-                num.push(*c);
-            } else {
-                break;
+        let signed = matches!(self.peek_nth(1), Some('+' | '-'));
+        let first_digit = self.peek_nth(if signed { 2 } else { 1 });
+        let leading = self.config.digit_separators.leading;
+        let starts_with_separator = leading
+            && matches!(self.config.allow_digit_separator, Choice::Yes(with) if first_digit == Some(with));
+        if !starts_with_separator && !first_digit.is_some_and(|c| c.is_ascii_digit()) {
+            if signed {
+                let exponent = self.mark();
+                self.consume(2);
+                return Err(TokenizationError::MalformedExponent(
+                    self.span_from(exponent),
+                ));
             }
-            self.consume(1);
+            return Ok((false, false));
         }
 
-        Ok(Token {
-            r#type: TokenType::Number(NumberType::Binary),
-            value: Box::new(num),
-            loc: Loc(start_ln, start_col),
-        })
+        self.consume(if signed { 2 } else { 1 });
+        Ok((
+            true,
+            self.parse_digits(char::is_ascii_digit, leading, DigitSeparatorRule::Leading)?,
+        ))
     }
 
-    fn parse_hex(&mut self) -> Result<Token, TokenizationError> {
-        let mut num = String::new();
+    /// Whether the cursor, right after the integer part of a number, sits on its decimal point
+    fn at_fraction(&self) -> bool {
+        if self.peek() != Some('.') {
+            return false;
+        }
+        match self.peek_nth(1) {
+            Some(c) if c.is_ascii_digit() => true,
+            Some('.') if self.config.protect_ranges => false,
+            // Left for the digit separator policy to judge
+            Some(c) if matches!(self.config.allow_digit_separator, Choice::Yes(with) if c == with) => {
+                true
+            }
+            _ => self.config.allow_trailing_dot,
+        }
+    }
 
-        let start_ln = self.ln;
-        let start_col = self.col;
-        self.consume(2);
+    /// Parses decimal numbers: integers (`12`), floats (`3.14`, `.25`, `1.`) and exponents
+    /// (`6.02E-23`)
+    fn parse_number(&mut self) -> Result<Token<'src>, TokenizationError> {
+        let start = self.mark();
+        let mut num_type = NumberType::Seq;
 
-        while let Some(c) = self.get_next_char() {
-            if c.is_ascii_hexdigit() {
-                num.push(*c);
-            } else {
-                break;
-            }
-            self.consume(1);
+        let mut separated =
+            self.parse_digits(char::is_ascii_digit, false, DigitSeparatorRule::Leading)?;
+        if self.at_fraction() {
+            self.bump();
+            num_type = NumberType::Float;
+            let adjacent_to_dot = self.config.digit_separators.adjacent_to_dot;
+            separated |= self.parse_digits(
+                char::is_ascii_digit,
+                adjacent_to_dot,
+                DigitSeparatorRule::AdjacentToDot,
+            )?;
         }
+        let (exponent, exponent_separated) = self.parse_exponent()?;
+        if exponent {
+            num_type = NumberType::Float;
+        }
+        separated |= exponent_separated;
+
+        let lexeme = self.slice_from(start);
+        let suffix = self.parse_number_suffix()?;
+        let value = match self.config.allow_digit_separator {
+            Choice::Yes(with) if separated => Cow::Owned(lexeme.replace(with, "")),
+            _ => Cow::Borrowed(lexeme),
+        };
+        let value = if lexeme.starts_with('.') {
+            Cow::Owned(format!("0{value}"))
+        } else {
+            value
+        };
 
-        Ok(Token {
-            r#type: TokenType::Number(NumberType::Hex),
-            value: Box::new(num),
-            loc: Loc(start_ln, start_col),
+        let mut token = self.token(TokenType::Number(num_type), value, start);
+        token.suffix = suffix.map(Cow::Borrowed);
+        Ok(token)
+    }
+
+    /// Whether the cursor sits on one of the configured number suffixes, followed by the end of
+    /// the word
+    fn at_number_suffix(&self) -> bool {
+        let rest = self.rest();
+        self.config.number_suffixes.iter().any(|suffix| {
+            rest.strip_prefix(suffix.as_str())
+                .is_some_and(|after| !after.starts_with(|c: char| c.is_alphanumeric() || c == '_'))
         })
     }
 
-    fn parse_octal(&mut self) -> Result<Token, TokenizationError> {
-        let mut num = String::new();
+    /// Consumes the suffix glued to a number, if it is one of the configured suffixes.
+    ///
+    /// Unknown suffixes are errors once suffixes are configured, and left untouched otherwise.
+    fn parse_number_suffix(&mut self) -> Result<Option<&'src str>, TokenizationError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if len == 0 || self.config.number_suffixes.is_empty() {
+            return Ok(None);
+        }
 
-        let start_ln = self.ln;
-        let start_col = self.col;
+        let suffix = &rest[..len];
+        if !self.config.number_suffixes.iter().any(|s| s == suffix) {
+            let start = self.mark();
+            self.consume(suffix.chars().count());
+            return Err(TokenizationError::UnknownNumberSuffix(
+                self.span_from(start),
+            ));
+        }
+        self.consume(suffix.chars().count());
+        Ok(Some(suffix))
+    }
+
+    /// Parses a prefixed number (`0x`, `0o`, `0b`, in any case) whose digits satisfy `is_digit`
+    fn parse_radix(
+        &mut self,
+        mut num_type: NumberType,
+        is_digit: fn(&char) -> bool,
+    ) -> Result<Token<'src>, TokenizationError> {
+        let start = self.mark();
         self.consume(2);
+        let digits_start = self.mark();
+        let after_radix_prefix = self.config.digit_separators.after_radix_prefix;
+        let mut separated = self.parse_digits(
+            is_digit,
+            after_radix_prefix,
+            DigitSeparatorRule::AfterRadixPrefix,
+        )?;
+        if num_type == NumberType::Hex && self.at_hex_float_tail() {
+            num_type = NumberType::HexFloat;
+            separated |= self.parse_hex_float_tail()?;
+        }
 
-        while let Some(c) = self.get_next_char() {
-            if matches!(*c, '0' | '1' | '2' | '3' | '4' | '5' | '6' | '7') {
-                num.push(*c);
-            } else {
-                break;
+        // Anything alphanumeric glued to the digits must be a known suffix
+        if let Some(c) = self.peek().filter(char::is_ascii_alphanumeric) {
+            if c.is_ascii_digit() || !self.at_number_suffix() {
+                let invalid = self.mark();
+                self.bump();
+                return Err(TokenizationError::InvalidDigitForRadix(
+                    self.span_from(invalid),
+                    c,
+                ));
             }
-            self.consume(1);
         }
+        let digits = self.slice_from(digits_start);
+        let mantissa = digits.split(['p', 'P']).next().unwrap_or_default();
+        if !mantissa.chars().any(|c| is_digit(&c)) {
+            return Err(TokenizationError::EmptyRadixLiteral(self.span_from(start)));
+        }
+        let suffix = self.parse_number_suffix()?;
+
+        let value = match self.config.allow_digit_separator {
+            Choice::Yes(with) if separated => Cow::Owned(digits.replace(with, "")),
+            _ => Cow::Borrowed(digits),
+        };
+        let mut token = self.token(TokenType::Number(num_type), value, start);
+        token.suffix = suffix.map(Cow::Borrowed);
+        Ok(token)
+    }
 
-        Ok(Token {
-            r#type: TokenType::Number(NumberType::Octal),
-            value: Box::new(num),
-            loc: Loc(start_ln, start_col),
+    /// Whether the cursor, right after the integer digits of a hexadecimal number, sits on the
+    /// fraction or the exponent of a hexadecimal float (e.g., `.8p3`, `p-2`)
+    fn at_hex_float_tail(&self) -> bool {
+        if !self.config.hex_floats {
+            return false;
+        }
+        let is_separator =
+            |c: char| matches!(self.config.allow_digit_separator, Choice::Yes(with) if c == with);
+        let rest = self.rest();
+        let rest = rest
+            .strip_prefix('.')
+            .map(|fraction| {
+                fraction.trim_start_matches(|c: char| c.is_ascii_hexdigit() || is_separator(c))
+            })
+            .unwrap_or(rest);
+        rest.strip_prefix(['p', 'P']).is_some_and(|exponent| {
+            let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            exponent.starts_with(|c: char| c.is_ascii_digit())
         })
     }
 
-    fn parse_string(&mut self, delim: Option<char>) -> Result<Token, TokenizationError> {
-        let mut string = String::new();
-        let mut is_escaped = false;
-        let start_ln = self.ln;
-        let start_col = self.col;
-        let delim = delim.unwrap_or('"');
+    /// Consumes the fraction and the binary exponent of a hexadecimal float.
+    ///
+    /// Returns whether a digit separator was encountered.
+    fn parse_hex_float_tail(&mut self) -> Result<bool, TokenizationError> {
+        let mut separated = false;
+        if self.peek() == Some('.') {
+            self.bump();
+            let adjacent_to_dot = self.config.digit_separators.adjacent_to_dot;
+            separated |= self.parse_digits(
+                char::is_ascii_hexdigit,
+                adjacent_to_dot,
+                DigitSeparatorRule::AdjacentToDot,
+            )?;
+        }
+        self.bump();
+        if matches!(self.peek(), Some('+' | '-')) {
+            self.bump();
+        }
+        separated |= self.parse_digits(char::is_ascii_digit, false, DigitSeparatorRule::Leading)?;
+        Ok(separated)
+    }
 
-        self.consume(1);
+    fn parse_binary(&mut self) -> Result<Token<'src>, TokenizationError> {
+        self.parse_radix(NumberType::Binary, |c| matches!(*c, '0' | '1'))
+    }
 
-        while let Some(c) = self.get_next_char() {
-            if *c == delim && !is_escaped {
-                self.consume(1);
-                break;
+    fn parse_hex(&mut self) -> Result<Token<'src>, TokenizationError> {
+        self.parse_radix(NumberType::Hex, char::is_ascii_hexdigit)
+    }
+
+    fn parse_octal(&mut self) -> Result<Token<'src>, TokenizationError> {
+        self.parse_radix(NumberType::Octal, |c| matches!(*c, '0'..='7'))
+    }
+
+    /// Consumes exactly `len` hexadecimal digits and returns their value
+    fn parse_hex_escape_digits(&mut self, len: usize) -> Option<u32> {
+        let digits = self.rest().get(..len)?;
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        self.consume(len);
+        u32::from_str_radix(digits, 16).ok()
+    }
+
+    /// Decodes the escape sequence under the cursor (starting at the backslash) into `out`.
+    ///
+    /// A backslash followed by `close`, the closing delimiter of the literal, stands for that
+    /// delimiter, and one followed by the first character of the interpolation opener (e.g. `\$`)
+    /// stands for that character. A backslash followed by a line break is a line continuation: the
+    /// line break and the leading whitespace of the next line are skipped. A backslash at the end
+    /// of the input is left for the caller to report as unterminated.
+    fn parse_escape(&mut self, out: &mut String, close: &str) -> Result<(), TokenizationError> {
+        let start = self.mark();
+        self.bump();
+
+        if !close.is_empty() && self.rest().starts_with(close) {
+            out.push_str(close);
+            self.consume(close.chars().count());
+            return Ok(());
+        }
+
+        if self.at_line_break() {
+            while self.peek().is_some_and(char::is_whitespace) {
+                self.bump();
             }
-            if *c == '\\' && !is_escaped {
-                is_escaped = true;
-                self.consume(1);
-                continue;
+            return Ok(());
+        }
+
+        let interpolation = close
+            .parse()
+            .ok()
+            .and_then(|quote| self.interpolation_open(quote))
+            .and_then(|open| open.chars().next());
+        let Some(escaped) = self.bump() else {
+            return Ok(());
+        };
+        match escaped {
+            'n' => out.push('\n'),
+            '0' => out.push('\0'),
+            't' => out.push('\t'),
+            'r' => out.push('\r'),
+            'b' => out.push('\u{8}'),
+            'f' => out.push('\u{c}'),
+            'v' => out.push('\u{b}'),
+            '\\' | '"' | '\'' => out.push(escaped),
+            'x' => {
+                let code = self
+                    .parse_hex_escape_digits(2)
+                    .ok_or_else(|| self.invalid_escape(start))?;
+                out.push(char::from_u32(code).ok_or_else(|| self.invalid_escape(start))?);
             }
-            if is_escaped {
-                match *c {
-                    'n' => {
-                        string.push('\n');
-                    }
-                    '0' => {
-                        string.push('\0');
-                    }
-                    't' => {
-                        string.push('\t');
-                    }
-                    'r' => {
-                        string.push('\r');
-                    }
-                    '\\' => {
-                        string.push('\\');
+            'u' if self.peek() == Some('{') => {
+                self.bump();
+                let len = self
+                    .rest()
+                    .find('}')
+                    .ok_or_else(|| self.invalid_escape(start))?;
+                if !(1..=6).contains(&len) {
+                    return Err(self.invalid_escape(start));
+                }
+                let code = self
+                    .parse_hex_escape_digits(len)
+                    .ok_or_else(|| self.invalid_escape(start))?;
+                self.bump();
+                out.push(char::from_u32(code).ok_or_else(|| self.invalid_escape(start))?);
+            }
+            'u' => {
+                let code = self
+                    .parse_hex_escape_digits(4)
+                    .ok_or_else(|| self.invalid_escape(start))?;
+                let code = if (0xD800..0xDC00).contains(&code) {
+                    // High surrogate, must be followed by an escaped low surrogate
+                    if !self.rest().starts_with("\\u") {
+                        return Err(self.invalid_escape(start));
                     }
-                    _ => {
-                        string.push_str(&format!("\\{c}"));
+                    self.consume(2);
+                    let low = self
+                        .parse_hex_escape_digits(4)
+                        .ok_or_else(|| self.invalid_escape(start))?;
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return Err(self.invalid_escape(start));
                     }
+                    0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00)
+                } else {
+                    code
+                };
+                out.push(char::from_u32(code).ok_or_else(|| self.invalid_escape(start))?);
+            }
+            c if Some(c) == interpolation => out.push(c),
+            _ => match self.config.unknown_escapes {
+                EscapePolicy::Error => return Err(self.invalid_escape(start)),
+                EscapePolicy::KeepRaw => {
+                    out.push('\\');
+                    out.push(escaped);
                 }
+                EscapePolicy::PassThrough => out.push(escaped),
+            },
+        }
 
-                is_escaped = false;
+        Ok(())
+    }
+
+    /// Moves the cursor up to `close` (or `open`, if not empty) after an invalid escape sequence,
+    /// so that [Tokenizer::tokenize_recovering] resumes after the literal
+    fn skip_literal(&mut self, close: &str, open: &str, multiline: bool) {
+        while let Some(c) = self.peek() {
+            let rest = self.rest();
+            if rest.starts_with(close)
+                || (!open.is_empty() && rest.starts_with(open))
+                || (!multiline && self.at_line_break())
+            {
+                break;
+            }
+            self.bump();
+            if c == '\\' {
+                self.bump();
+            }
+        }
+    }
+
+    /// Reports the escape sequence starting at `start` and ending at the cursor as invalid
+    fn invalid_escape(&self, start: Pos) -> TokenizationError {
+        TokenizationError::InvalidEscape(self.span_from(start))
+    }
+
+    /// Parses a string enclosed by `open` and `close`, processing escape sequences
+    fn parse_string(
+        &mut self,
+        open: &str,
+        close: &str,
+        multiline: bool,
+    ) -> Result<Token<'src>, TokenizationError> {
+        let start = self.mark();
+        self.consume(open.chars().count());
+        let content_start = self.pos;
+        let open = self.span_from(start);
+        // Only allocated once an escape sequence rewrites the content
+        let mut owned: Option<String> = None;
+
+        let close_start = loop {
+            let Some(c) = self.peek() else {
+                return Err(TokenizationError::UnterminatedString(self.span_from(start)));
+            };
+            if self.rest().starts_with(close) {
+                let close_start = self.mark();
+                self.consume(close.chars().count());
+                break close_start;
+            }
+            if !multiline && self.at_line_break() {
+                return Err(TokenizationError::UnterminatedString(self.span_from(start)));
+            }
+            if c == '\\' {
+                let string =
+                    owned.get_or_insert_with(|| self.src[content_start..self.pos].to_string());
+                if let Err(err) = self.parse_escape(string, close) {
+                    self.skip_literal(close, "", multiline);
+                    if self.rest().starts_with(close) {
+                        self.consume(close.chars().count());
+                    }
+                    return Err(err);
+                }
             } else {
-                string.push(*c);
+                if let Some(string) = owned.as_mut() {
+                    string.push(c);
+                }
+                self.bump();
             }
-            self.consume(1);
+        };
+
+        let value = match owned {
+            Some(string) => Cow::Owned(string),
+            None => Cow::Borrowed(&self.src[content_start..close_start.offset]),
+        };
+
+        let mut token = self.token(TokenType::String, value, start);
+        token.string = Some(StringInfo {
+            raw: false,
+            open,
+            close: self.span_from(close_start),
+        });
+        Ok(token)
+    }
+
+    /// Detects a configured string delimiter at the cursor, preferring the longest opening one
+    fn string_delimiter_start(&self) -> Option<(String, String)> {
+        let rest = self.rest();
+        self.config
+            .string_delimiters
+            .iter()
+            .filter(|(open, close)| {
+                !open.is_empty() && !close.is_empty() && rest.starts_with(open.as_str())
+            })
+            .max_by_key(|(open, _)| open.len())
+            .cloned()
+    }
+
+    /// Detects a triple-quoted string opening at the cursor, returning its quotes
+    fn triple_quote_start(&self) -> Option<&'static str> {
+        if !self.config.triple_quoted_strings {
+            return None;
         }
+        ["\"\"\"", "\'\'\'"]
+            .into_iter()
+            .find(|quotes| self.rest().starts_with(quotes))
+    }
 
-        Ok(Token {
-            r#type: TokenType::String,
-            value: Box::new(string),
-            loc: Loc(start_ln, start_col),
+    /// Detects a heredoc opening (`<<TAG` followed by the end of the line) at the cursor,
+    /// returning its tag
+    fn heredoc_start(&self) -> Option<&'src str> {
+        if !self.config.heredocs {
+            return None;
+        }
+        let after = self.rest().strip_prefix("<<")?;
+        let len = after
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(after.len());
+        let (tag, line) = after.split_at(len);
+        if !tag.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            return None;
+        }
+        let line = line.trim_start_matches(|c: char| c.is_whitespace() && c != '\n' && c != '\r');
+        (line.starts_with('\n') || line.starts_with("\r\n")).then_some(tag)
+    }
+
+    /// Parses a heredoc: the lines following `<<TAG`, up to a line made of `TAG` only (leading
+    /// and trailing whitespace allowed)
+    fn parse_heredoc(&mut self, tag: &'src str) -> Result<Token<'src>, TokenizationError> {
+        let start = self.mark();
+        self.consume(2 + tag.chars().count());
+        let open = self.span_from(start);
+
+        while !self.at_line_break() {
+            self.bump();
+        }
+        self.consume(if self.peek() == Some('\r') { 2 } else { 1 });
+        let body_start = self.pos;
+
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(TokenizationError::UnterminatedString(self.span_from(start)));
+            }
+            let line = rest.split('\n').next().unwrap_or(rest);
+            if line.trim() == tag {
+                let body = &self.src[body_start..self.pos];
+                let body = body
+                    .strip_suffix('\n')
+                    .map(|body| body.strip_suffix('\r').unwrap_or(body))
+                    .unwrap_or(body);
+
+                let indent = line.len() - line.trim_start().len();
+                self.consume(line[..indent].chars().count());
+                let close_start = self.mark();
+                self.consume(tag.chars().count());
+
+                let mut token = self.token(TokenType::String, Cow::Borrowed(body), start);
+                token.string = Some(StringInfo {
+                    raw: false,
+                    open,
+                    close: self.span_from(close_start),
+                });
+                return Ok(token);
+            }
+            self.consume(line.chars().count() + 1);
+        }
+    }
+
+    /// Detects a raw string opening at the cursor, returning the length of its prefix in
+    /// characters, the number of hashes and the quote used.
+    ///
+    /// Fences with too many hashes are detected too, to be reported by
+    /// [Tokenizer::parse_raw_string].
+    fn raw_string_start(&self) -> Option<(usize, usize, char)> {
+        let rest = self.rest();
+        self.config.raw_string_prefixes.iter().find_map(|prefix| {
+            let after = rest
+                .strip_prefix(prefix.as_str())
+                .filter(|_| !prefix.is_empty())?;
+            let hashes = after.len() - after.trim_start_matches('#').len();
+            let quote = after[hashes..].chars().next()?;
+            matches!(quote, '"' | '\'').then(|| (prefix.chars().count(), hashes, quote))
         })
     }
 
-    fn parse_char(&mut self) -> Result<Token, TokenizationError> {
+    /// Parses a raw string (e.g., `r"..."`, `r#"..."#`), where backslashes have no meaning
+    fn parse_raw_string(
+        &mut self,
+        prefix_len: usize,
+        hashes: usize,
+        quote: char,
+    ) -> Result<Token<'src>, TokenizationError> {
+        let start = self.mark();
+        self.consume(prefix_len + hashes + 1);
+        let open = self.span_from(start);
+        if hashes > self.config.raw_string_max_hashes {
+            return Err(TokenizationError::RawStringFenceTooLong(open));
+        }
+
+        let close_start = loop {
+            let Some(c) = self.peek() else {
+                return Err(TokenizationError::UnterminatedString(self.span_from(start)));
+            };
+            if c == quote {
+                let after = &self.rest()[quote.len_utf8()..];
+                if after.len() - after.trim_start_matches('#').len() >= hashes {
+                    let close_start = self.mark();
+                    self.consume(1 + hashes);
+                    break close_start;
+                }
+            }
+            if !self.config.allow_multiline_strings && self.at_line_break() {
+                return Err(TokenizationError::UnterminatedString(self.span_from(start)));
+            }
+            self.bump();
+        };
+
+        let value = Cow::Borrowed(&self.src[open.end.offset..close_start.offset]);
+        let mut token = self.token(TokenType::String, value, start);
+        token.string = Some(StringInfo {
+            raw: true,
+            open,
+            close: self.span_from(close_start),
+        });
+        Ok(token)
+    }
+
+    fn parse_char(&mut self) -> Result<Token<'src>, TokenizationError> {
         if self.config.parse_char_as_string {
-            self.parse_string(Some('\''))
+            return self.parse_string("'", "'", self.config.allow_multiline_strings);
+        }
+
+        let start = self.mark();
+        self.bump();
+        let content_start = self.pos;
+        let mut owned: Option<String> = None;
+
+        let content_end = loop {
+            let Some(c) = self.peek() else {
+                return Err(TokenizationError::UnterminatedChar(self.span_from(start)));
+            };
+            if c == '\'' {
+                let content_end = self.pos;
+                self.bump();
+                break content_end;
+            }
+            if self.at_line_break() {
+                return Err(TokenizationError::UnterminatedChar(self.span_from(start)));
+            }
+            if c == '\\' {
+                let chr =
+                    owned.get_or_insert_with(|| self.src[content_start..self.pos].to_string());
+                if let Err(err) = self.parse_escape(chr, "'") {
+                    self.skip_literal("'", "", false);
+                    if self.peek() == Some('\'') {
+                        self.bump();
+                    }
+                    return Err(err);
+                }
+            } else {
+                if let Some(chr) = owned.as_mut() {
+                    chr.push(c);
+                }
+                self.bump();
+            }
+        };
+
+        let chr = match owned {
+            Some(chr) => Cow::Owned(chr),
+            None => Cow::Borrowed(&self.src[content_start..content_end]),
+        };
+
+        if chr.chars().count() != 1 {
+            Err(TokenizationError::NotAValidChar(self.span_from(start)))
         } else {
-            let mut chr = String::new();
-            let mut is_escaped = false;
-            let start_ln = self.ln;
-            let start_col = self.col;
+            Ok(self.token(TokenType::Char, chr, start))
+        }
+    }
 
-            self.consume(1);
+    /// Parses the next token, remembering it for [Tokenizer::at_signed_number]
+    fn next_token(&mut self) -> Option<Result<Token<'src>, TokenizationError>> {
+        let next = self.scan_token();
+        if let Some(Ok(token)) = &next {
+            if !matches!(
+                token.r#type,
+                TokenType::Whitespace | TokenType::Newline | TokenType::Comment
+            ) {
+                let expects_operand = matches!(
+                    token.r#type,
+                    TokenType::Operator | TokenType::Symbol | TokenType::InterpolationStart
+                ) && !matches!(token.value.as_ref(), ")" | "]" | "}");
+                self.prev = Some((expects_operand, token.span.end.line));
+            }
+        }
+        next
+    }
 
-            while let Some(c) = self.get_next_char() {
-                if *c == '\'' && !is_escaped {
-                    self.consume(1);
-                    break;
+    /// Parses the next token, skipping blank space. Returns [None] once the input is exhausted
+    fn scan_token(&mut self) -> Option<Result<Token<'src>, TokenizationError>> {
+        self.token_start = self.mark();
+        if let Some(Mode::String {
+            close,
+            multiline,
+            start,
+        }) = self.modes.last().copied()
+        {
+            return Some(self.parse_string_part(close, multiline, start));
+        }
+
+        while let Some(next_char) = self.peek() {
+            if self.config.emit_newlines && self.at_line_break() {
+                return Some(Ok(self.parse_newline()));
+            }
+            if next_char.is_whitespace() {
+                if self.config.emit_whitespace {
+                    return Some(Ok(self.parse_whitespace()));
                 }
-                if *c == '\\' && !is_escaped {
-                    is_escaped = true;
-                    self.consume(1);
-                    continue;
+                self.bump();
+                continue;
+            }
+
+            self.token_start = self.mark();
+            if let Some(kind) = self.comment_start() {
+                match self.parse_comment(kind) {
+                    Ok(_) if !self.config.emit_comments => continue,
+                    comment => return Some(comment),
                 }
-                chr.push(*c);
-                is_escaped = false;
-                self.consume(1);
             }
 
-            let out_type = if !self.config.parse_char_as_string {
-                TokenType::Char
+            if let Some(Mode::Interpolation { depth }) = self.modes.last_mut() {
+                match next_char {
+                    '{' => *depth += 1,
+                    '}' if *depth == 0 => {
+                        self.modes.pop();
+                        return Some(Ok(self.single(TokenType::InterpolationEnd, 1)));
+                    }
+                    '}' => *depth -= 1,
+                    _ => {}
+                }
+            }
+
+            return Some(self.dispatch(next_char));
+        }
+
+        // The input ended inside an interpolation
+        self.token_start = self.mark();
+        self.modes.iter().rev().find_map(|mode| match mode {
+            Mode::String { start, .. } => Some(Err(TokenizationError::UnterminatedString(
+                self.span_from(*start),
+            ))),
+            Mode::Interpolation { .. } => None,
+        })
+    }
+
+    /// Returns the delimiter opening interpolations in strings quoted by `quote`, if any
+    fn interpolation_open(&self, quote: char) -> Option<&str> {
+        match quote {
+            '"' => self.config.string_interpolation.as_deref(),
+            '`' if self.config.template_literals => {
+                Some(self.config.string_interpolation.as_deref().unwrap_or("${"))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the string starting at the cursor contains an interpolation before its end
+    fn has_interpolation(&self, close: char, multiline: bool) -> bool {
+        let Some(open) = self.interpolation_open(close) else {
+            return false;
+        };
+        let mut rest = &self.rest()[close.len_utf8()..];
+        while let Some(c) = rest.chars().next() {
+            if rest.starts_with(open) {
+                return true;
+            }
+            if c == close || (!multiline && matches!(c, '\n' | '\r')) {
+                return false;
+            }
+            // Skipping the escaped character along with the backslash
+            let len = if c == '\\' {
+                rest.chars().take(2).map(char::len_utf8).sum()
             } else {
-                TokenType::String
+                c.len_utf8()
             };
+            rest = &rest[len..];
+        }
+        false
+    }
+
+    /// Opens an interpolated string, the rest of it is lexed by [Tokenizer::parse_string_part]
+    fn parse_string_start(&mut self, close: char, multiline: bool) -> Token<'src> {
+        self.modes.push(Mode::String {
+            close,
+            multiline,
+            start: self.mark(),
+        });
+        self.single(TokenType::StringStart, 1)
+    }
+
+    /// Parses the next piece of an interpolated string: a fragment of text, the start of an
+    /// interpolation or the closing quote
+    fn parse_string_part(
+        &mut self,
+        close: char,
+        multiline: bool,
+        string_start: Pos,
+    ) -> Result<Token<'src>, TokenizationError> {
+        let open = self
+            .interpolation_open(close)
+            .unwrap_or_default()
+            .to_string();
+
+        if self.peek() == Some(close) {
+            self.modes.pop();
+            return Ok(self.single(TokenType::StringEnd, 1));
+        }
+        if self.rest().starts_with(&open) {
+            self.modes.push(Mode::Interpolation { depth: 0 });
+            return Ok(self.single(TokenType::InterpolationStart, open.chars().count()));
+        }
+
+        let start = self.mark();
+        let mut owned: Option<String> = None;
+        while let Some(c) = self.peek() {
+            if c == close || self.rest().starts_with(&open) {
+                break;
+            }
+            if !multiline && self.at_line_break() {
+                return Err(TokenizationError::UnterminatedString(
+                    self.span_from(string_start),
+                ));
+            }
+            if c == '\\' {
+                let string = owned.get_or_insert_with(|| self.slice_from(start).to_string());
+                let mut buf = [0; 4];
+                let close = close.encode_utf8(&mut buf);
+                if let Err(err) = self.parse_escape(string, close) {
+                    self.skip_literal(close, &open, multiline);
+                    return Err(err);
+                }
+            } else {
+                if let Some(string) = owned.as_mut() {
+                    string.push(c);
+                }
+                self.bump();
+            }
+        }
+        if self.peek().is_none() {
+            return Err(TokenizationError::UnterminatedString(
+                self.span_from(string_start),
+            ));
+        }
+
+        let value = match owned {
+            Some(string) => Cow::Owned(string),
+            None => Cow::Borrowed(self.slice_from(start)),
+        };
+        Ok(self.token(TokenType::StringFragment, value, start))
+    }
+
+    fn parse_newline(&mut self) -> Token<'src> {
+        let len = if self.peek() == Some('\r') { 2 } else { 1 };
+        self.single(TokenType::Newline, len)
+    }
+
+    /// Consumes a run of whitespace, stopping at line breaks when those are emitted on their own
+    fn parse_whitespace(&mut self) -> Token<'src> {
+        let start = self.mark();
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() || (self.config.emit_newlines && self.at_line_break()) {
+                break;
+            }
+            self.bump();
+        }
+        self.token(
+            TokenType::Whitespace,
+            Cow::Borrowed(self.slice_from(start)),
+            start,
+        )
+    }
+
+    /// Whether the `n`th character from the cursor starts a number (a digit, a dot followed by
+    /// a digit or a special float)
+    fn at_number(&self, n: usize) -> bool {
+        match self.peek_nth(n) {
+            Some('.') => {
+                let after_dot = n == 0 && self.src[..self.pos].ends_with('.');
+                self.config.allow_leading_dot
+                    && !(self.config.protect_ranges && after_dot)
+                    && self.peek_nth(n + 1).is_some_and(|c| c.is_ascii_digit())
+            }
+            Some(c) if c.is_ascii_digit() => true,
+            _ => self.special_float_len(n).is_some(),
+        }
+    }
+
+    /// Returns the length of the special float (`inf`, `nan`, ...) starting `n` ASCII characters
+    /// after the cursor, if [TokenizerConfig::special_floats] is set
+    fn special_float_len(&self, n: usize) -> Option<usize> {
+        if !self.config.special_floats {
+            return None;
+        }
+        let rest = self.rest().get(n..)?;
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        ["inf", "infinity", "nan"]
+            .iter()
+            .any(|special| rest[..len].eq_ignore_ascii_case(special))
+            .then_some(len)
+    }
 
-            if out_type == TokenType::Char && chr.len() > 1 {
-                Err(TokenizationError::NotAValidChar(Loc(start_ln, start_col)))
+    /// Whether a `+` or `-` at the cursor is the sign of a number, according to
+    /// [TokenizerConfig::signed_numbers]
+    fn at_signed_number(&self) -> bool {
+        if self.config.ignore_numbers
+            || !matches!(self.peek(), Some('+' | '-'))
+            || !self.at_number(1)
+        {
+            return false;
+        }
+        match self.config.signed_numbers {
+            SignedNumbers::Never => false,
+            SignedNumbers::Always => true,
+            SignedNumbers::AfterOperator => match self.prev {
+                Some((expects_operand, line)) if line == self.ln => expects_operand,
+                _ => true,
+            },
+        }
+    }
+
+    /// Parses a number preceded by its sign, which is kept in the value
+    fn parse_signed_number(&mut self) -> Result<Token<'src>, TokenizationError> {
+        let start = self.mark();
+        let sign = self.bump().unwrap_or_default();
+        let after_sign = self.pos;
+        let mut token = self.parse_unsigned_number()?;
+
+        token.value = match token.value {
+            // Digits written right after the sign keep borrowing from the source
+            Cow::Borrowed(digits) if digits.as_ptr() == self.src[after_sign..].as_ptr() => {
+                Cow::Borrowed(&self.src[start.offset..after_sign + digits.len()])
+            }
+            digits => Cow::Owned(format!("{sign}{digits}")),
+        };
+        token.loc = start.loc();
+        token.span.start = start;
+        Ok(token)
+    }
+
+    /// Detects a date and/or time at the cursor, returning its length in bytes and whether it
+    /// exists
+    fn date_time_start(&self) -> Option<(usize, bool)> {
+        if !self.config.date_time_literals || self.config.ignore_numbers {
+            return None;
+        }
+        datetime::match_date_time(self.rest())
+    }
+
+    /// Parses a date and/or time of `len` bytes, reporting impossible ones
+    fn parse_date_time(
+        &mut self,
+        len: usize,
+        valid: bool,
+    ) -> Result<Token<'src>, TokenizationError> {
+        let start = self.mark();
+        self.consume(len);
+        if !valid {
+            return Err(TokenizationError::InvalidDateTime(self.span_from(start)));
+        }
+        Ok(self.token(
+            TokenType::DateTime,
+            Cow::Borrowed(self.slice_from(start)),
+            start,
+        ))
+    }
+
+    /// Returns the length in bytes of the duration (e.g., `30s`, `1.5h`, `1h30m`) at the
+    /// cursor, if any
+    fn duration_len(&self) -> Option<usize> {
+        if self.config.ignore_numbers {
+            return None;
+        }
+        let rest = self.rest();
+        let mut len = 0;
+        while rest[len..].starts_with(|c: char| c.is_ascii_digit()) {
+            len += rest[len..]
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len() - len);
+            if let Some(fraction) = rest[len..].strip_prefix('.') {
+                let digits = fraction
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(fraction.len());
+                if digits > 0 {
+                    len += 1 + digits;
+                }
+            }
+            // Longest unit first, so that `ms` is not read as `m` followed by `s`
+            let unit = self
+                .config
+                .duration_units
+                .iter()
+                .filter(|unit| !unit.is_empty() && rest[len..].starts_with(unit.as_str()))
+                .max_by_key(|unit| unit.len())?;
+            len += unit.len();
+        }
+        let running_into_word = rest[len..].starts_with(|c: char| c.is_alphanumeric() || c == '_');
+        (len > 0 && !running_into_word).then_some(len)
+    }
+
+    /// Parses a number without sign, picking the parser matching its prefix
+    fn parse_unsigned_number(&mut self) -> Result<Token<'src>, TokenizationError> {
+        if let Some(len) = self.special_float_len(0) {
+            let start = self.mark();
+            self.consume(len);
+            let value = Cow::Borrowed(self.slice_from(start));
+            return Ok(self.token(TokenType::Number(NumberType::Float), value, start));
+        }
+        if self.peek() == Some('0') {
+            match self.peek_nth(1) {
+                Some('x' | 'X') => return self.parse_hex(),
+                Some('o' | 'O') => return self.parse_octal(),
+                Some('b' | 'B') => return self.parse_binary(),
+                _ => {}
+            }
+        }
+        self.parse_number()
+    }
+
+    /// Picks the right parser for a token starting with `next_char`
+    fn dispatch(&mut self, next_char: char) -> Result<Token<'src>, TokenizationError> {
+        if let Some((len, valid)) = self.date_time_start() {
+            self.parse_date_time(len, valid)
+        } else if let Some(len) = self.duration_len() {
+            let start = self.mark();
+            self.consume(len);
+            let value = Cow::Borrowed(self.slice_from(start));
+            Ok(self.token(TokenType::Duration, value, start))
+        } else if self.at_signed_number() {
+            self.parse_signed_number()
+        } else if !self.config.ignore_numbers && self.at_number(0) {
+            self.parse_unsigned_number()
+        } else if let Some((prefix_len, hashes, quote)) = self.raw_string_start() {
+            self.parse_raw_string(prefix_len, hashes, quote)
+        } else if let Some(quotes) = self.triple_quote_start() {
+            self.parse_string(quotes, quotes, true)
+        } else if let Some(tag) = self.heredoc_start() {
+            self.parse_heredoc(tag)
+        } else if let Some((open, close)) = self.string_delimiter_start() {
+            self.parse_string(&open, &close, self.config.allow_multiline_strings)
+        } else if next_char == '"' {
+            let multiline = self.config.allow_multiline_strings;
+            if self.has_interpolation('"', multiline) {
+                Ok(self.parse_string_start('"', multiline))
+            } else {
+                self.parse_string("\"", "\"", multiline)
+            }
+        } else if next_char == '`' && self.config.template_literals {
+            if self.has_interpolation('`', true) {
+                Ok(self.parse_string_start('`', true))
             } else {
-                Ok(Token {
-                    r#type: out_type,
-                    value: Box::new(chr),
-                    loc: Loc(start_ln, start_col),
-                })
+                self.parse_string("`", "`", true)
             }
+        } else if next_char == '\'' {
+            self.parse_char()
+        } else if let Some(len) = self.operator_len().filter(|len| {
+            // A single character registered both ways stays a symbol
+            *len > 1 || (next_char != '.' && !self.config.consider_as_symbols.contains(&next_char))
+        }) {
+            Ok(self.single(TokenType::Operator, len))
+        } else if next_char == '.' || self.config.consider_as_symbols.contains(&next_char) {
+            Ok(self.single(TokenType::Symbol, 1))
+        } else if self.is_word_start(next_char) {
+            self.parse_word()
+        } else {
+            let start = self.mark();
+            self.bump();
+            Err(TokenizationError::UnexpectedCharacter(
+                self.span_from(start),
+                next_char,
+            ))
         }
     }
 
+    /// Returns a lazy iterator over the remaining tokens, borrowing the tokenizer.
+    ///
+    /// Iteration stops after the first [TokenizationError] is yielded, and the tokenizer then
+    /// yields nothing more. When iteration stops early (e.g., with `take` or `break`), the
+    /// tokenizer can still be inspected or resumed once the iterator is dropped.
+    pub fn tokens(&mut self) -> Tokens<'_, 'src> {
+        Tokens { tokenizer: self }
+    }
+
     /// Tokenizes the input and returns a list of Tokens or a [TokenizationError] if parsing fails
-    pub fn tokenize(mut self) -> Result<Vec<Token>, TokenizationError> {
+    pub fn tokenize(self) -> Result<Vec<Token<'src>>, TokenizationError> {
+        self.collect()
+    }
+
+    /// Tokenizes the whole input, collecting every [TokenizationError] instead of stopping at the
+    /// first one.
+    ///
+    /// Each invalid piece of input is replaced by a [TokenType::Error] token, running from the
+    /// start of the faulty token up to the next separator (whitespace, symbol, operator or the `}`
+    /// closing an interpolation). A literal with an invalid escape sequence is skipped up to its
+    /// closing delimiter.
+    pub fn tokenize_recovering(mut self) -> (Vec<Token<'src>>, Vec<TokenizationError>) {
         let mut tokens = vec![];
-        while self.is_out_of_bound() != OutOfBound::Out {
-            if self.is_out_of_bound() == OutOfBound::Empty {
-                self.next_line();
-            } else {
-                let next_char = self.get_next_char().unwrap();
-                if matches!(next_char, ' ') {
-                    self.consume(1);
-                } else if matches!(next_char, '0'..='9') && !self.config.ignore_numbers {
-                    let first_digit = *self.get_next_char().unwrap();
-                    if first_digit == '0' {
-                        if let Some(c) = self.peek_tok() {
-                            match *c {
-                                'x' => {
-                                    tokens.push(self.parse_hex()?);
-                                }
-                                'o' => {
-                                    tokens.push(self.parse_octal()?);
-                                }
-                                'b' => {
-                                    tokens.push(self.parse_binary()?);
-                                }
-                                '.' => {
-                                    tokens.push(self.parse_float()?);
-                                }
-                                _ => {
-                                    tokens.push(self.parse_number()?);
-                                }
-                            };
-                        } else {
-                            tokens.push(self.parse_number()?);
-                        }
-                    } else {
-                        tokens.push(self.parse_number()?);
-                    }
-                } else if matches!(next_char, '.') {
-                    if let Some(c) = self.peek_tok() {
-                        if *c == '\n' {
-                            tokens.push(Token {
-                                r#type: TokenType::Symbol,
-                                value: Box::new(".".into()),
-                                loc: Loc(self.ln, self.col),
-                            });
-                            self.consume(1);
-                        } else if c.is_ascii_digit() && !self.config.ignore_numbers {
-                            tokens.push(self.parse_float()?);
-                        } else {
-                            tokens.push(Token {
-                                r#type: TokenType::Symbol,
-                                value: Box::new(".".into()),
-                                loc: Loc(self.ln, self.col),
-                            });
-                            self.consume(1);
-                        }
-                    } else {
-                        tokens.push(Token {
-                            r#type: TokenType::Symbol,
-                            value: Box::new(".".into()),
-                            loc: Loc(self.ln, self.col),
-                        });
-                        self.consume(1);
+        let mut errors = vec![];
+        while let Some(next) = self.next_token() {
+            match next {
+                Ok(token) => tokens.push(token),
+                Err(err) => {
+                    let token = self.recover(&err);
+                    errors.push(err);
+                    if !token.span.is_empty() {
+                        tokens.push(token);
                     }
-                } else if matches!(next_char, '"') {
-                    tokens.push(self.parse_string(None)?);
-                } else if matches!(next_char, '\'') {
-                    tokens.push(self.parse_char()?);
-                } else if self.config.consider_as_symbols.contains(&next_char) {
-                    tokens.push(Token {
-                        r#type: TokenType::Symbol,
-                        value: Box::new(next_char.to_string()),
-                        loc: Loc(self.ln, self.col),
-                    });
-                    self.consume(1);
-                } else if self.config.consider_as_operators.contains(&next_char) {
-                    tokens.push(Token {
-                        r#type: TokenType::Operator,
-                        value: Box::new(next_char.to_string()),
-                        loc: Loc(self.ln, self.col),
-                    });
-                    self.consume(1);
-                } else {
-                    tokens.push(self.parse_word()?)
                 }
             }
         }
+        (tokens, errors)
+    }
+
+    /// Skips to the next separator after `err`, returning the skipped input as a
+    /// [TokenType::Error] token
+    fn recover(&mut self, err: &TokenizationError) -> Token<'src> {
+        let start = self.token_start;
+        if self.pos == start.offset {
+            self.bump();
+        }
+        // A literal with an invalid escape sequence was already skipped up to its end
+        if !matches!(err, TokenizationError::InvalidEscape(_)) {
+            while let Some(c) = self.peek() {
+                if c.is_whitespace()
+                    || self.config.consider_as_symbols.contains(&c)
+                    || self.operator_len().is_some()
+                    || (matches!(c, '{' | '}') && !self.modes.is_empty())
+                {
+                    break;
+                }
+                self.bump();
+            }
+        }
+        if self.peek().is_none() {
+            // Unclosed interpolated strings would report the end of the input forever
+            self.modes.clear();
+        }
+
+        self.token(
+            TokenType::Error,
+            Cow::Borrowed(self.slice_from(start)),
+            start,
+        )
+    }
+}
+
+impl<'src> Iterator for Tokenizer<'src> {
+    type Item = Result<Token<'src>, TokenizationError>;
+
+    /// Produces the next token, or the error that stopped the tokenization.
+    ///
+    /// After an error has been yielded, the iterator is exhausted.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let next = self.next_token();
+        if matches!(next, Some(Err(_))) {
+            self.failed = true;
+        }
+        next
+    }
+}
+
+impl std::iter::FusedIterator for Tokenizer<'_> {}
+
+/// Lazy iterator over the tokens of a [Tokenizer], created by [Tokenizer::tokens]
+pub struct Tokens<'a, 'src> {
+    tokenizer: &'a mut Tokenizer<'src>,
+}
+
+impl<'src> Iterator for Tokens<'_, 'src> {
+    type Item = Result<Token<'src>, TokenizationError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.tokenizer.next()
+    }
+}
+
+impl std::iter::FusedIterator for Tokens<'_, '_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn implicit_keyword_ids_follow_explicit_ones() {
+        let tokens = Tokenizer::builder()
+            .add_keywords(&["let", "fn"])
+            .add_keyword_with_id("if", 5)
+            .add_keywords(&["else", "while"])
+            .build("let fn if else while")
+            .tokenize()
+            .unwrap();
+        let types: Vec<_> = tokens.iter().map(|token| token.r#type).collect();
+        assert_eq!(
+            types,
+            [0, 1, 5, 6, 7].map(TokenType::Keyword),
+            "ids must stay distinct"
+        );
+    }
+
+    #[test]
+    fn char_literals_hold_exactly_one_character() {
+        let chr = |src| Tokenizer::builder().build(src).tokenize();
+        assert_eq!(chr("'a'").unwrap()[0].value, "a");
+        assert_eq!(chr("'\\n'").unwrap()[0].value, "\n");
+        assert!(matches!(
+            chr("''"),
+            Err(TokenizationError::NotAValidChar(_))
+        ));
+        assert!(matches!(
+            chr("'ab'"),
+            Err(TokenizationError::NotAValidChar(_))
+        ));
+    }
+
+    #[test]
+    fn custom_delimiters_escape_their_closing_text() {
+        for policy in [EscapePolicy::KeepRaw, EscapePolicy::Error] {
+            let tokens = Tokenizer::builder()
+                .add_string_delimiter("«", "»")
+                .add_string_delimiter("<<<", ">>>")
+                .unknown_escapes(policy)
+                .build(r#"«a\»b» <<<c\>>>d>>> "e\"f""#)
+                .tokenize()
+                .unwrap();
+            let values: Vec<_> = tokens.iter().map(|token| token.value.as_ref()).collect();
+            assert_eq!(values, ["a»b", "c>>>d", "e\"f"]);
+        }
+    }
+
+    fn lex(
+        builder: TokenizerBuilder,
+        src: &str,
+    ) -> Result<Vec<(TokenType, String)>, TokenizationError> {
+        let tokens = builder.string_interpolation("${").build(src).tokenize()?;
+        Ok(tokens
+            .into_iter()
+            .map(|token| (token.r#type, token.value.into_owned()))
+            .collect())
+    }
+
+    #[test]
+    fn braces_inside_interpolations_follow_the_config() {
+        use TokenType::*;
+        let src = r#""${ {a} }""#;
+        let expected = |brace| {
+            [
+                (StringStart, "\""),
+                (InterpolationStart, "${"),
+                (brace, "{"),
+                (Word, "a"),
+                (brace, "}"),
+                (InterpolationEnd, "}"),
+                (StringEnd, "\""),
+            ]
+            .map(|(r#type, value)| (r#type, value.to_string()))
+        };
+        let symbols = Tokenizer::builder().add_symbols(&['{', '}']);
+        assert_eq!(lex(symbols, src).unwrap(), expected(Symbol));
+        let operators = Tokenizer::builder().add_operators(&['{', '}']);
+        assert_eq!(lex(operators, src).unwrap(), expected(Operator));
+        assert_eq!(lex(Tokenizer::builder(), src).unwrap(), expected(Word));
+        assert!(matches!(
+            lex(
+                Tokenizer::builder().identifier_policy(IdentifierPolicy::Ascii),
+                src
+            ),
+            Err(TokenizationError::UnexpectedCharacter(_, '{'))
+        ));
+    }
+
+    #[test]
+    fn interpolation_modes_nest() {
+        use TokenType::*;
+        let builder = || {
+            Tokenizer::builder()
+                .line_comment("//")
+                .block_comment("/*", "*/")
+        };
+        let types = |src| {
+            lex(builder(), src)
+                .unwrap()
+                .into_iter()
+                .map(|(r#type, _)| r#type)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            types(r#""a${ "b${c}" }d""#),
+            [
+                StringStart,
+                StringFragment,
+                InterpolationStart,
+                StringStart,
+                StringFragment,
+                InterpolationStart,
+                Word,
+                InterpolationEnd,
+                StringEnd,
+                InterpolationEnd,
+                StringFragment,
+                StringEnd,
+            ]
+        );
+        // Braces in comments and strings do not close the interpolation
+        assert_eq!(
+            types("\"${ a /* } */ \"}\" // }\n}\""),
+            [
+                StringStart,
+                InterpolationStart,
+                Word,
+                String,
+                InterpolationEnd,
+                StringEnd,
+            ]
+        );
+        assert!(matches!(
+            lex(builder(), r#""a${ "b${c" }"#),
+            Err(TokenizationError::UnterminatedString(_))
+        ));
+        assert!(matches!(
+            lex(builder(), r#""a${ b"#),
+            Err(TokenizationError::UnterminatedString(_))
+        ));
+    }
+
+    #[test]
+    fn escaped_interpolation_opener() {
+        for policy in [EscapePolicy::KeepRaw, EscapePolicy::Error] {
+            let builder = Tokenizer::builder().unknown_escapes(policy);
+            assert_eq!(
+                lex(builder.clone(), r#""a\${b}""#).unwrap(),
+                [(TokenType::String, "a${b}".to_string())]
+            );
+            let tokens = lex(builder, r#""\${a}${b}""#).unwrap();
+            assert_eq!(tokens[1], (TokenType::StringFragment, "${a}".to_string()));
+        }
+    }
+
+    #[test]
+    fn recovering_skips_only_the_faulty_input() {
+        let builder = || {
+            Tokenizer::builder()
+                .add_symbols(&[';'])
+                .add_operators(&['+'])
+                .unknown_escapes(EscapePolicy::Error)
+        };
+        let cases = [
+            (builder(), r#""a\q b" "c";"#, vec![r#""a\q b""#], "String(c) Symbol(;)"),
+            (builder(), r"'\q' 'c';", vec![r"'\q'"], "Char(c) Symbol(;)"),
+            (
+                builder().add_string_delimiter("<<", ">>"),
+                r"<<a\q b>> c",
+                vec![r"<<a\q b>>"],
+                "Word(c)",
+            ),
+            (
+                builder().string_interpolation("${"),
+                r#""a\q ${x} b\q" c"#,
+                vec![r"a\q ", r" b\q"],
+                "StringStart(\") InterpolationStart(${) Word(x) InterpolationEnd(}) StringEnd(\") Word(c)",
+            ),
+            (
+                builder().string_interpolation("${"),
+                r#""a ${0b2} b" c"#,
+                vec!["0b2"],
+                "StringStart(\") StringFragment(a ) InterpolationStart(${) InterpolationEnd(}) \
+                 StringFragment( b) StringEnd(\") Word(c)",
+            ),
+            (builder(), "0x;", vec!["0x"], "Symbol(;)"),
+            (builder(), "'ab';", vec!["'ab'"], "Symbol(;)"),
+            (builder(), "'ab c", vec!["'ab c"], ""),
+            (builder(), "\"ab c", vec!["\"ab c"], ""),
+            (builder().block_comment("/*", "*/"), "a /* b", vec!["/* b"], "Word(a)"),
+            (builder(), "1e+ a", vec!["1e+"], "Word(a)"),
+            (builder().add_number_suffix("u8"), "1q;", vec!["1q"], "Symbol(;)"),
+            (
+                builder().identifier_policy(IdentifierPolicy::Ascii),
+                "a \u{a7} b",
+                vec!["\u{a7}"],
+                "Word(a) Word(b)",
+            ),
+            (builder().date_time_literals(true), "2024-13-01;", vec!["2024-13-01"], "Symbol(;)"),
+            (builder().raw_strings(&["r"], 1), r###"r##"x"## b"###, vec![r###"r##"x"##"###], "Word(b)"),
+            (
+                builder().allow_digit_separator(Choice::Yes('_')),
+                "1__0;",
+                vec!["1__0"],
+                "Symbol(;)",
+            ),
+        ];
+
+        for (builder, src, expected_errors, expected_tokens) in cases {
+            let (tokens, errors) = builder.build(src).tokenize_recovering();
+            assert_eq!(errors.len(), expected_errors.len(), "{src}: {errors:?}");
+            let (faulty, valid): (Vec<_>, Vec<_>) = tokens
+                .iter()
+                .partition(|token| token.r#type == TokenType::Error);
+            let faulty: Vec<_> = faulty.iter().map(|token| token.value.as_ref()).collect();
+            assert_eq!(faulty, expected_errors, "{src}");
+            let valid: Vec<_> = valid
+                .iter()
+                .map(|token| format!("{:?}({})", token.r#type, token.value))
+                .collect();
+            assert_eq!(valid.join(" "), expected_tokens, "{src}");
+        }
+    }
+
+    #[test]
+    fn signs_after_closing_delimiters_stay_operators() {
+        let values = |src| {
+            Tokenizer::builder()
+                .add_symbols(&['(', ')', '[', ']', '{', '}', ','])
+                .add_operators(&['-', '='])
+                .signed_numbers(SignedNumbers::AfterOperator)
+                .build(src)
+                .tokenize()
+                .unwrap()
+                .into_iter()
+                .map(|token| token.value.into_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values("(a)-1"), ["(", "a", ")", "-", "1"]);
+        assert_eq!(values("x[0]-1"), ["x", "[", "0", "]", "-", "1"]);
+        assert_eq!(values("{a}-1"), ["{", "a", "}", "-", "1"]);
+        assert_eq!(values("f(-1,-2)"), ["f", "(", "-1", ",", "-2", ")"]);
+        assert_eq!(values("[-1]"), ["[", "-1", "]"]);
+        assert_eq!(values("a=-1"), ["a", "=", "-1"]);
+    }
+
+    /// Lexes `src` as a single number, returning the separator rule it breaks, if any
+    fn broken_separator_rule(
+        policy: DigitSeparatorPolicy,
+        separator: char,
+        src: &str,
+    ) -> Option<DigitSeparatorRule> {
+        let result = Tokenizer::builder()
+            .allow_digit_separator(Choice::Yes(separator))
+            .digit_separator_policy(policy)
+            .hex_floats(true)
+            .build(src)
+            .tokenize();
+        match result {
+            Ok(tokens) => {
+                assert_eq!(tokens.len(), 1, "{src}");
+                None
+            }
+            Err(TokenizationError::UnexpectedDigitSeparator(_, rule)) => Some(rule),
+            Err(err) => panic!("{src}: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn strict_digit_separators() {
+        use DigitSeparatorRule::*;
+        let rule = |src| broken_separator_rule(DigitSeparatorPolicy::strict(), '_', src);
+        assert_eq!(rule("1_000"), None);
+        assert_eq!(rule("1_000.000_1e1_0"), None);
+        assert_eq!(rule("1__000"), Some(Consecutive));
+        assert_eq!(rule("1_"), Some(Trailing));
+        assert_eq!(rule("1_e5"), Some(Trailing));
+        assert_eq!(rule("1_.5"), Some(AdjacentToDot));
+        assert_eq!(rule("1._5"), Some(AdjacentToDot));
+        assert_eq!(rule("0x_ff"), Some(AfterRadixPrefix));
+        assert_eq!(rule("0xff_"), Some(Trailing));
+
+        // C++ digit separators
+        let rule = |src| broken_separator_rule(DigitSeparatorPolicy::strict(), '\'', src);
+        assert_eq!(rule("1'000'000"), None);
+        assert_eq!(rule("1''000"), Some(Consecutive));
+
+        let err = Tokenizer::builder()
+            .allow_digit_separator(Choice::Yes('_'))
+            .build("1__000")
+            .tokenize()
+            .unwrap_err();
+        assert_eq!(err.diagnostic().label, "several separators in a row");
+    }
+
+    #[test]
+    fn rust_digit_separators() {
+        use DigitSeparatorRule::*;
+        let rule = |src| broken_separator_rule(DigitSeparatorPolicy::rust(), '_', src);
+        assert_eq!(rule("1__0"), None);
+        assert_eq!(rule("1_"), None);
+        assert_eq!(rule("1_e5"), None);
+        assert_eq!(rule("1e_5"), None);
+        assert_eq!(rule("0x_ff_"), None);
+        assert_eq!(rule("1_.5"), Some(AdjacentToDot));
+        assert_eq!(rule("1._5"), Some(AdjacentToDot));
+        assert_eq!(rule("0x_"), Some(NotFollowedByDigit));
+    }
+
+    #[test]
+    fn java_digit_separators() {
+        use DigitSeparatorRule::*;
+        let rule = |src| broken_separator_rule(DigitSeparatorPolicy::java(), '_', src);
+        assert_eq!(rule("1__000"), None);
+        assert_eq!(rule("0xff__ff"), None);
+        assert_eq!(rule("1_"), Some(Trailing));
+        assert_eq!(rule("1_.5"), Some(AdjacentToDot));
+        assert_eq!(rule("0x_ff"), Some(AfterRadixPrefix));
+    }
+
+    #[test]
+    fn python_digit_separators() {
+        use DigitSeparatorRule::*;
+        let rule = |src| broken_separator_rule(DigitSeparatorPolicy::python(), '_', src);
+        assert_eq!(rule("1_000"), None);
+        assert_eq!(rule("0x_ff"), None);
+        assert_eq!(rule("1__000"), Some(Consecutive));
+        assert_eq!(rule("0x__ff"), Some(Consecutive));
+        assert_eq!(rule("1_"), Some(Trailing));
+        assert_eq!(rule("1._5"), Some(AdjacentToDot));
+    }
+
+    #[test]
+    fn tokens_resume_after_stopping_early_but_not_after_an_error() {
+        let mut tokenizer = Tokenizer::builder().build("a b c");
+        let first: Vec<_> = tokenizer.tokens().take(1).collect();
+        assert_eq!(first.len(), 1);
+        assert_eq!(tokenizer.next().unwrap().unwrap().value, "b");
+
+        let mut tokenizer = Tokenizer::builder().build("a \"b c");
+        assert!(tokenizer.tokens().any(|token| token.is_err()));
+        assert!(tokenizer.next().is_none());
+    }
+
+    #[test]
+    fn raw_string_fence_too_long() {
+        for policy in [IdentifierPolicy::Permissive, IdentifierPolicy::Ascii] {
+            let src = r###"a r##"x"## b"###;
+            let result = Tokenizer::builder()
+                .raw_strings(&["r"], 1)
+                .identifier_policy(policy)
+                .build(src)
+                .tokenize();
+            match result {
+                Err(TokenizationError::RawStringFenceTooLong(span)) => {
+                    assert_eq!(&src[span.range()], "r##\"")
+                }
+                other => panic!("unexpected result {other:?}"),
+            }
+        }
 
-        Ok(tokens)
+        let tokens = Tokenizer::builder()
+            .raw_strings(&["r"], 1)
+            .build(r##"r#"x"#"##)
+            .tokenize()
+            .unwrap();
+        assert_eq!(tokens[0].value, "x");
     }
 }
@@ -0,0 +1,138 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::Loc;
+
+/// Unit in which columns are counted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ColumnEncoding {
+    /// Unicode scalar values, as in [Loc]
+    #[default]
+    Chars,
+    /// UTF-8 bytes
+    Utf8,
+    /// UTF-16 code units, as expected by the Language Server Protocol
+    Utf16,
+    /// Extended grapheme clusters, i.e. user-perceived characters
+    Graphemes,
+    /// Display width in a terminal, with tabs advancing to the next multiple of `tab_width`
+    Visual { tab_width: usize },
+}
+
+impl ColumnEncoding {
+    /// Returns the column reached after `text`, starting from `column`
+    fn advance(self, column: usize, text: &str) -> usize {
+        match self {
+            ColumnEncoding::Chars => column + text.chars().count(),
+            ColumnEncoding::Utf8 => column + text.len(),
+            ColumnEncoding::Utf16 => column + text.encode_utf16().count(),
+            ColumnEncoding::Graphemes => column + text.graphemes(true).count(),
+            ColumnEncoding::Visual { tab_width } => {
+                let mut column = column;
+                for (i, part) in text.split('\t').enumerate() {
+                    if i > 0 && tab_width > 0 {
+                        column += tab_width - column % tab_width;
+                    }
+                    column += part.width();
+                }
+                column
+            }
+        }
+    }
+}
+
+/// Lookup table over the lines of a source, converting between byte offsets, [Loc] and columns
+/// in any [ColumnEncoding]
+///
+/// Lines are split on `\n`, like the tokenizer does.
+#[derive(Clone, Debug)]
+pub struct LineIndex<'src> {
+    src: &'src str,
+    /// Byte offset of the start of each line
+    line_starts: Vec<usize>,
+}
+
+impl<'src> LineIndex<'src> {
+    /// Indexes the lines of `src`
+    pub fn new(src: &'src str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(src.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { src, line_starts }
+    }
+
+    /// Returns the number of lines of the source
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the content of the line (0-based index), without its line break
+    pub fn line(&self, line: usize) -> Option<&'src str> {
+        let text = self.raw_line(line)?;
+        Some(text.strip_suffix('\r').unwrap_or(text))
+    }
+
+    /// Returns the line, including a `\r` preceding its `\n` as the tokenizer counts it as a column
+    fn raw_line(&self, line: usize) -> Option<&'src str> {
+        let start = *self.line_starts.get(line)?;
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .map_or(self.src.len(), |next| next - 1);
+        Some(&self.src[start..end])
+    }
+
+    /// Converts a byte offset into a [Loc], or [None] if it is out of the source or not on a
+    /// character boundary
+    pub fn loc(&self, offset: usize) -> Option<Loc> {
+        if !self.src.is_char_boundary(offset) {
+            return None;
+        }
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let column = self.src[self.line_starts[line]..offset].chars().count();
+        Some(Loc(line, column))
+    }
+
+    /// Converts a [Loc] into a byte offset, or [None] if it is out of the source
+    pub fn offset(&self, loc: Loc) -> Option<usize> {
+        let text = self.raw_line(loc.0)?;
+        let column = match text.char_indices().nth(loc.1) {
+            Some((i, _)) => i,
+            None if text.chars().count() == loc.1 => text.len(),
+            None => return None,
+        };
+        Some(self.line_starts[loc.0] + column)
+    }
+
+    /// Returns the column of `loc` counted in `encoding`, or [None] if it is out of the source.
+    ///
+    /// A [Loc] in the middle of a grapheme cluster counts the whole cluster.
+    pub fn column(&self, loc: Loc, encoding: ColumnEncoding) -> Option<usize> {
+        let offset = self.offset(loc)?;
+        Some(encoding.advance(0, &self.src[self.line_starts[loc.0]..offset]))
+    }
+
+    /// Converts a `column` of `line` counted in `encoding` back into a [Loc], or [None] if it is
+    /// past the end of the line.
+    ///
+    /// A column falling inside a character (e.g., the second half of a wide character) resolves
+    /// to the start of that character.
+    pub fn to_loc(&self, line: usize, column: usize, encoding: ColumnEncoding) -> Option<Loc> {
+        let text = self.raw_line(line)?;
+        let pieces: Box<dyn Iterator<Item = &str>> = match encoding {
+            ColumnEncoding::Graphemes => Box::new(text.graphemes(true)),
+            _ => Box::new(text.split_inclusive(|_| true)),
+        };
+
+        let (mut reached, mut chars) = (0, 0);
+        for piece in pieces {
+            let next = encoding.advance(reached, piece);
+            if next > column {
+                return Some(Loc(line, chars));
+            }
+            reached = next;
+            chars += piece.chars().count();
+        }
+        (reached == column).then_some(Loc(line, chars))
+    }
+}
@@ -0,0 +1,379 @@
+use std::fmt::Display;
+use std::num::IntErrorKind;
+
+use crate::error::NumberError;
+
+/// Arbitrary-precision unsigned integer, used for number tokens that don't fit in `u128`
+///
+/// Format: Formats BigUint in base 10.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct BigUint {
+    /// Base 2^32 limbs, least significant first, without trailing zero limbs
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    /// Parses a sequence of digits in the given radix (2 to 36)
+    pub fn from_str_radix(digits: &str, radix: u32) -> Result<Self, NumberError> {
+        if digits.is_empty() {
+            return Err(NumberError::Invalid);
+        }
+
+        let mut big = BigUint::default();
+        for c in digits.chars() {
+            let digit = c.to_digit(radix).ok_or(NumberError::Invalid)?;
+            big.mul_add(radix, digit);
+        }
+        Ok(big)
+    }
+
+    /// Whether the number equals zero
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Converts to `u128`, or [None] if the number doesn't fit
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0u128, |acc, limb| (acc << 32) | *limb as u128),
+        )
+    }
+
+    /// Converts to the nearest `f64` (ties to even), which may be infinite
+    pub fn to_f64(&self) -> f64 {
+        self.to_f64_scaled(0)
+    }
+
+    /// Converts `self * 2^exp` to the nearest `f64` (ties to even), which may be infinite or
+    /// subnormal
+    pub(crate) fn to_f64_scaled(&self, exp: i64) -> f64 {
+        let bits = self.bit_len();
+        if bits == 0 {
+            return 0.0;
+        }
+        // The value lies in [2^top, 2^(top + 1))
+        let top = (bits as i64 - 1).saturating_add(exp);
+        if top > f64::MAX_EXP as i64 - 1 {
+            return f64::INFINITY;
+        }
+        // Significant bits that fit in the result, fewer for subnormals
+        let keep = (f64::MANTISSA_DIGITS as i64).min(top + 1074 + 1);
+        if keep < 0 {
+            return 0.0;
+        }
+
+        let drop = bits as i64 - keep;
+        let mantissa = if drop <= 0 {
+            self.bits_from(0)
+        } else {
+            let drop = drop as u64;
+            let truncated = self.bits_from(drop) & ((1u64 << keep) - 1);
+            let half = self.bit(drop - 1);
+            let sticky = self.any_bit_below(drop - 1);
+            if half && (sticky || truncated & 1 == 1) {
+                truncated + 1
+            } else {
+                truncated
+            }
+        };
+        // The mantissa has at most 54 bits, and the scaled result is representable
+        ldexp(mantissa as f64, exp + drop.max(0))
+    }
+
+    /// Number of significant bits
+    fn bit_len(&self) -> u64 {
+        match self.limbs.last() {
+            Some(last) => self.limbs.len() as u64 * 32 - last.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    /// Returns the bit at `index`, counting from the least significant one
+    fn bit(&self, index: u64) -> bool {
+        let limb = self.limbs.get((index / 32) as usize).copied().unwrap_or(0);
+        limb >> (index % 32) & 1 == 1
+    }
+
+    /// Whether any bit below `index` is set
+    fn any_bit_below(&self, index: u64) -> bool {
+        let (full, partial) = ((index / 32) as usize, index % 32);
+        self.limbs[..full.min(self.limbs.len())]
+            .iter()
+            .any(|limb| *limb != 0)
+            || self
+                .limbs
+                .get(full)
+                .is_some_and(|limb| limb & ((1u32 << partial) - 1) != 0)
+    }
+
+    /// Returns the 64 bits starting at `index`, counting from the least significant one
+    fn bits_from(&self, index: u64) -> u64 {
+        let (first, shift) = ((index / 32) as usize, index % 32);
+        let window = (0..3).fold(0u128, |acc, i| {
+            let limb = self.limbs.get(first + i).copied().unwrap_or(0);
+            acc | (limb as u128) << (32 * i)
+        });
+        (window >> shift) as u64
+    }
+
+    /// Computes `self * mul + add` in place
+    fn mul_add(&mut self, mul: u32, add: u32) {
+        let mut carry = add as u64;
+        for limb in self.limbs.iter_mut() {
+            let wide = *limb as u64 * mul as u64 + carry;
+            *limb = wide as u32;
+            carry = wide >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    /// Divides in place by `div`, returning the remainder
+    fn div_rem(&mut self, div: u32) -> u32 {
+        let mut rem = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let wide = (rem << 32) | *limb as u64;
+            *limb = (wide / div as u64) as u32;
+            rem = wide % div as u64;
+        }
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        rem as u32
+    }
+}
+
+/// Parses the value of a hexadecimal float without its prefix (e.g., `1.8p3`, `-1p-2`)
+pub(crate) fn parse_hex_float(value: &str) -> Result<f64, NumberError> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let (mantissa, exponent) = value.split_once(['p', 'P']).ok_or(NumberError::Invalid)?;
+    let (int, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    // Exponents out of range are clamped, the result being infinite or zero anyway
+    let exponent = match exponent.parse::<i64>() {
+        Ok(exponent) => exponent,
+        Err(err) if *err.kind() == IntErrorKind::PosOverflow => i64::MAX,
+        Err(err) if *err.kind() == IntErrorKind::NegOverflow => i64::MIN,
+        Err(_) => return Err(NumberError::Invalid),
+    };
+
+    // Each hexadecimal digit of the fraction shifts the mantissa by 4 bits
+    let digits = BigUint::from_str_radix(&format!("{int}{fraction}"), 16)?;
+    let shift = exponent.saturating_sub(4 * fraction.len() as i64);
+    let magnitude = digits.to_f64_scaled(shift);
+    Ok(if negative { -magnitude } else { magnitude })
+}
+
+/// Computes `x * 2^exp`, without the intermediate underflow of `2f64.powi(exp)` for subnormal
+/// results
+fn ldexp(x: f64, exp: i64) -> f64 {
+    // Smallest exponent of a normal `f64`, whose power of two is still representable
+    const MIN_NORMAL_EXP: i64 = -1022;
+    if exp < MIN_NORMAL_EXP {
+        x * 2f64.powi(MIN_NORMAL_EXP as i32) * 2f64.powi((exp - MIN_NORMAL_EXP) as i32)
+    } else {
+        x * 2f64.powi(exp as i32)
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // Groups of 9 decimal digits, least significant first
+        let mut groups = vec![];
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            groups.push(rest.div_rem(1_000_000_000));
+        }
+
+        let mut groups = groups.iter().rev();
+        if let Some(first) = groups.next() {
+            write!(f, "{first}")?;
+        }
+        for group in groups {
+            write!(f, "{group:09}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SignedNumbers, Token, Tokenizer};
+
+    fn number(src: &str) -> Token<'_> {
+        let mut tokens = Tokenizer::builder()
+            .signed_numbers(SignedNumbers::Always)
+            .build(src)
+            .tokenize()
+            .unwrap();
+        assert_eq!(tokens.len(), 1, "{src} is not a single token");
+        tokens.remove(0)
+    }
+
+    #[test]
+    fn big_uint_round_trips_through_display() {
+        let digits = "340282366920938463463374607431768211456123";
+        assert_eq!(
+            BigUint::from_str_radix(digits, 10).unwrap().to_string(),
+            digits
+        );
+        assert_eq!(BigUint::from_str_radix("0", 10).unwrap().to_string(), "0");
+        assert_eq!(BigUint::from_str_radix("", 10), Err(NumberError::Invalid));
+        assert_eq!(
+            BigUint::from_str_radix("12a", 10),
+            Err(NumberError::Invalid)
+        );
+    }
+
+    #[test]
+    fn to_f64_rounds_once() {
+        // Three limbs, with bits below the 53 kept ones on both sides of the halfway point
+        let big = BigUint::from_str_radix("2000000000000100000001", 16).unwrap();
+        assert_eq!(big.to_f64(), 0x2000000000000100000001u128 as f64);
+
+        for value in [
+            u128::MAX,
+            (1 << 100) + (1 << 47),
+            (1 << 100) + (1 << 47) + 1,
+            (1 << 100) + (3 << 47),
+            0x1234_5678_9abc_def0_1234_5678,
+        ] {
+            let big = BigUint::from_str_radix(&value.to_string(), 10).unwrap();
+            assert_eq!(big.to_f64(), value as f64, "{value}");
+        }
+    }
+
+    #[test]
+    fn to_f64_overflows_to_infinity() {
+        let big = BigUint::from_str_radix(&"f".repeat(300), 16).unwrap();
+        assert_eq!(big.to_f64(), f64::INFINITY);
+    }
+
+    #[test]
+    fn integer_accessors() {
+        assert_eq!(number("255").as_u64(), Ok(255));
+        assert_eq!(number("0xff").as_u128(), Ok(255));
+        assert_eq!(number("0b1010").as_i64(), Ok(10));
+        assert_eq!(number("0o777").as_i128(), Ok(511));
+        assert_eq!(number("-42").as_i64(), Ok(-42));
+        assert_eq!(number("-0").as_u64(), Ok(0));
+        assert_eq!(number("+7").as_u64(), Ok(7));
+        assert_eq!(
+            number("-170141183460469231731687303715884105728").as_i128(),
+            Ok(i128::MIN)
+        );
+    }
+
+    #[test]
+    fn integer_accessors_overflow() {
+        assert_eq!(
+            number("18446744073709551616").as_u64(),
+            Err(NumberError::Overflow)
+        );
+        assert_eq!(
+            number("9223372036854775808").as_i64(),
+            Err(NumberError::Overflow)
+        );
+        assert_eq!(
+            number("-9223372036854775809").as_i64(),
+            Err(NumberError::Overflow)
+        );
+        assert_eq!(number("-1").as_u128(), Err(NumberError::Overflow));
+        assert_eq!(number("-1").as_big(), Err(NumberError::Overflow));
+        assert_eq!(
+            number("340282366920938463463374607431768211456").as_u128(),
+            Err(NumberError::Overflow)
+        );
+        assert_eq!(
+            number("340282366920938463463374607431768211456")
+                .as_big()
+                .map(|big| big.to_string()),
+            Ok("340282366920938463463374607431768211456".to_string())
+        );
+    }
+
+    fn hex_float(src: &str) -> Result<f64, NumberError> {
+        let tokens = Tokenizer::builder()
+            .hex_floats(true)
+            .signed_numbers(SignedNumbers::Always)
+            .build(src)
+            .tokenize()
+            .unwrap();
+        assert_eq!(tokens.len(), 1, "{src} is not a single token");
+        tokens[0].as_f64()
+    }
+
+    #[test]
+    fn hex_floats() {
+        assert_eq!(hex_float("0x1.8p3"), Ok(12.0));
+        assert_eq!(hex_float("0x1p-2"), Ok(0.25));
+        assert_eq!(hex_float("0x.8p1"), Ok(1.0));
+        assert_eq!(hex_float("-0x1.8p3"), Ok(-12.0));
+        assert_eq!(hex_float("0x0p0"), Ok(0.0));
+        assert_eq!(hex_float("0x1.fffffffffffffp1023"), Ok(f64::MAX));
+        assert_eq!(hex_float("0x1p1024"), Ok(f64::INFINITY));
+        // Rounded once, ties to even
+        assert_eq!(hex_float("0x1.00000000000008p0"), Ok(1.0));
+        assert_eq!(
+            hex_float("0x1.00000000000018p0"),
+            Ok(1.0 + 2.0 * f64::EPSILON)
+        );
+        assert_eq!(
+            hex_float("0x2000000000000100000001p0"),
+            Ok(0x2000000000000100000001u128 as f64)
+        );
+    }
+
+    #[test]
+    fn hex_floats_subnormal() {
+        assert_eq!(hex_float("0x1p-1074"), Ok(f64::from_bits(1)));
+        assert_eq!(hex_float("0x1p-1024"), Ok(f64::from_bits(1 << 50)));
+        assert_eq!(hex_float("0x1p-1022"), Ok(f64::MIN_POSITIVE));
+        assert_eq!(hex_float("0x1.8p-1074"), Ok(f64::from_bits(2)));
+        assert_eq!(hex_float("0x1p-1075"), Ok(0.0));
+        assert_eq!(hex_float("0x1.1p-1075"), Ok(f64::from_bits(1)));
+        assert_eq!(hex_float("0x1p-2000"), Ok(0.0));
+    }
+
+    #[test]
+    fn hex_floats_with_huge_exponents() {
+        assert_eq!(hex_float("0x3p9223372036854775807"), Ok(f64::INFINITY));
+        assert_eq!(hex_float("0x3p99999999999999999999"), Ok(f64::INFINITY));
+        assert_eq!(
+            hex_float("-0x3p99999999999999999999"),
+            Ok(f64::NEG_INFINITY)
+        );
+        assert_eq!(hex_float("0x3p-9223372036854775808"), Ok(0.0));
+        assert_eq!(hex_float("0x3.8p-99999999999999999999"), Ok(0.0));
+        assert_eq!(hex_float("0x0p99999999999999999999"), Ok(0.0));
+        assert_eq!(
+            BigUint::from_str_radix("3", 16)
+                .unwrap()
+                .to_f64_scaled(i64::MIN),
+            0.0
+        );
+    }
+
+    #[test]
+    fn accessors_reject_other_tokens() {
+        assert_eq!(number("1.5").as_u64(), Err(NumberError::NotAnInteger));
+        assert_eq!(number("1.5").as_f64(), Ok(1.5));
+        assert_eq!(number("word").as_f64(), Err(NumberError::NotANumber));
+        assert_eq!(number("0x10").as_f64(), Ok(16.0));
+        assert_eq!(number("-3").as_f64(), Ok(-3.0));
+    }
+}
@@ -0,0 +1,57 @@
+/// Prefix tree over operator strings, used to find the longest operator at a position
+#[derive(Debug, Clone)]
+pub(crate) struct Trie {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    children: Vec<(char, usize)>,
+    terminal: bool,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Self {
+            nodes: vec![Node::default()],
+        }
+    }
+}
+
+impl Trie {
+    pub(crate) fn insert(&mut self, word: &str) {
+        if word.is_empty() {
+            return;
+        }
+
+        let mut node = 0;
+        for c in word.chars() {
+            node = match self.nodes[node].children.iter().find(|(k, _)| *k == c) {
+                Some((_, child)) => *child,
+                None => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.push((c, child));
+                    child
+                }
+            };
+        }
+        self.nodes[node].terminal = true;
+    }
+
+    /// Returns the length, in characters, of the longest inserted word prefixing `input`
+    pub(crate) fn longest_match(&self, input: &str) -> Option<usize> {
+        let mut node = 0;
+        let mut longest = None;
+        for (len, c) in input.chars().enumerate() {
+            match self.nodes[node].children.iter().find(|(k, _)| *k == c) {
+                Some((_, child)) => node = *child,
+                None => break,
+            }
+            if self.nodes[node].terminal {
+                longest = Some(len + 1);
+            }
+        }
+        longest
+    }
+}